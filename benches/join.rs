@@ -0,0 +1,35 @@
+// Compare `join`, which reserves space using the iterator's size hint,
+// with growing the string one value at a time.
+// Run with `cargo bench`.
+extern crate easy_shortcuts as es;
+use es::traits::*;
+use std::time::{Duration,Instant};
+
+fn grow_join<T: AsRef<str>, I: Iterator<Item=T>>(iter: I, delim: char) -> String {
+    let mut res = String::new();
+    for v in iter {
+        res.push_str(v.as_ref());
+        res.push(delim);
+    }
+    res.pop();
+    res
+}
+
+fn time<F: FnMut() -> usize>(label: &str, rounds: u32, mut f: F) -> Duration {
+    let start = Instant::now();
+    let mut total = 0;
+    for _ in 0..rounds {
+        total += f();
+    }
+    let elapsed = start.elapsed();
+    println!("{:<12} {:>8.2?} per round ({} bytes)",label,elapsed / rounds,total / rounds as usize);
+    elapsed
+}
+
+fn main() {
+    let words = (0..1_000_000).map(|i| format!("w{}",i % 1000)).to_vec();
+    let rounds = 20;
+    let grown = time("grow",rounds,|| grow_join(words.iter(),',').len());
+    let reserved = time("join",rounds,|| words.iter().join(',').len());
+    println!("join takes {:.0}% of the time",100.0 * reserved.as_secs_f64() / grown.as_secs_f64());
+}
@@ -0,0 +1,18 @@
+extern crate easy_shortcuts as es;
+use es::traits::*;
+
+fn main() {
+    let dir = es::argn_or(1,".");
+    // every file below dir, depth-first, skipping build output
+    es::all_paths(&dir)
+        .exclude("target")
+        .files_only()
+        // only what changed in the last minute:
+        // .modified_since(Duration::from_secs(60))
+        // only Rust sources, or only things called .git:
+        // .extension("rs")
+        // .name(".git")
+        // hidden entries are skipped unless we ask for them:
+        // .show_all()
+        .map(|(p,m)| (p,m.len())).debug("\n");
+}
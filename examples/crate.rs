@@ -28,13 +28,13 @@ fn main() {
         if ! d.is_dir() { continue; }
         let filename = path_file_name(&p);
         if let Some(endc) = filename.rfind('-') {
-            if &filename[0..endc] == crate_name {
+            if filename[0..endc] == crate_name {
                 crates.push((p,semver_i(&filename[endc+1..])));
             }
         }
     }
     // crate versions in ascending order by semver rules    
-    crates.sort_by(|a,b| a.1.cmp(&b.1));
+    crates.sort_by_key(|a| a.1);
     let path = crates.last().or_die("no such crate");
     println!("{}",path.0.display());    
 }
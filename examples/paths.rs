@@ -9,8 +9,7 @@ fn main() {
         }
     }
     // and now, loopless
-    // filter is slightly awkward because we get references to the tuples
     es::paths(&dir)
-		.filter(|&(_,ref m)| m.is_file())
+		.filter_meta(|m| m.is_file())
 		.map(|(p,m)| (p,m.len())).debug("\n");
 }
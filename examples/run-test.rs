@@ -11,8 +11,7 @@ use std::process;
 fn get_crate_name() -> String {
     let mut crate_dir = env::current_dir().unwrap();
     crate_dir.pop();
-    let crate_name = crate_dir.file_name().or_die("can't get crate");
-    crate_name.to_str().unwrap().replace('-',"_").to_string()
+    es::crate_name_from_manifest(&crate_dir).or_die("can't get crate name from Cargo.toml")
 }
 
 fn append_indented(dest: &mut String, src: &str, indent: &str) {
@@ -27,7 +26,7 @@ fn main() {
     let examples = "../examples";
     let (special,question) = kind.split_at(1);
     let indent = if special == "/" {"    "} else {""};
-    let question = if question == "?" {true} else {false};
+    let question = question == "?";
 
     let mut template = String::new();
     let mut before = String::new();
@@ -70,7 +69,7 @@ fn main() {
         .output().or_die("could not run cargo");
 
     println!("{}", String::from_utf8_lossy(&output.stderr));
-    if output.stdout.len() > 0 {
+    if ! output.stdout.is_empty() {
         println!("WARNING - tests will suppress this output ******");
         println!("{}", String::from_utf8_lossy(&output.stdout));
     }
@@ -14,7 +14,7 @@ fn indent_line(line: &str) -> String {
     format!("    {}\n",line)
 }
 
-const PRELUDE: &'static str = "
+const PRELUDE: &str = "
 #![allow(unused_imports)]
 #![allow(dead_code)]
 use std::fs;
@@ -51,11 +51,10 @@ fn prelude_and_cache() -> (String,PathBuf) {
 }
 
 fn main() {
-    // we are going to put the expanded source and resulting exe in temp
-    let out_dir = "temp";
-    if ! fs::metadata(out_dir).is_dir() {
-        fs::create_dir(out_dir).or_die("cannot create temp directory here");
-    }
+    // we are going to put the expanded source and resulting exe in a
+    // temporary directory, which goes away when we are finished
+    let tmp = es::temp_dir_guard("runner");
+    let out_dir = tmp.path();
 
     let file = PathBuf::from(es::argn_err(1,"please supply a source file"));
     let ext = file.extension().or_die("no file extension");
@@ -77,8 +76,8 @@ fn main() {
         {
             let mut lines = code.lines();
             for line in lines.by_ref() {
-                let line = line.trim_left();
-                if line.starts_with("//") || line.len() == 0 ||
+                let line = line.trim_start();
+                if line.starts_with("//") || line.is_empty() ||
                     line.starts_with("extern ") || line.starts_with("use ") {
                     prefix += line;
                     prefix.push('\n');
@@ -102,15 +101,15 @@ fn main() {
         ",prefix,body);
     }
 
-    let mut out_file = PathBuf::from(out_dir);
-    out_file.push(&file);
+    let mut out_file = out_dir.to_path_buf();
+    out_file.push(file.file_name().or_die("no file name"));
     let mut program = out_file.clone();
     program.set_extension("");
 
     es::write_all(&out_file,&code);
 
     let mut builder = process::Command::new("rustc");
-    builder.args(&["-C","prefer-dynamic"]).args(&["-C","debuginfo=0"])
+    builder.args(["-C","prefer-dynamic"]).args(["-C","debuginfo=0"])
            .arg("-L").arg(&cache);
     let status = builder.arg("-o").arg(&program)
         .arg(&out_file)
@@ -0,0 +1,149 @@
+//! Packing a directory tree into a single file, without needing `tar`.
+//!
+//! The format is a simple sequence of records. Each record is a kind
+//! byte (`d` for a directory, `f` for a file), the length of the relative
+//! path as a little-endian `u32`, the path itself with `/` separators, and
+//! for files the length of the contents as a little-endian `u64` followed
+//! by the contents.
+
+use std::io;
+use std::io::prelude::*;
+use std::fs;
+use std::path::{Path,PathBuf,Component};
+use traits::*;
+
+/// pack the contents of `dir` into the archive file `out`,
+/// quitting on any i/o error, or if a file name is not valid UTF-8.
+///
+/// ```
+/// extern crate easy_shortcuts as es;
+/// # let dir = std::env::temp_dir().join("easy-shortcuts-doc-archive");
+/// # let _ = std::fs::remove_dir_all(&dir);
+/// # std::fs::create_dir_all(dir.join("src/nested")).unwrap();
+/// # es::write_all(dir.join("src/hello.txt"),"hello");
+/// let packed = std::env::temp_dir().join("easy-shortcuts-doc.archive");
+///
+/// es::archive(&dir,&packed);
+/// std::fs::remove_dir_all(&dir).unwrap();
+/// es::unarchive(&packed,&dir);
+/// assert_eq!(es::read_to_string(dir.join("src/hello.txt")),"hello");
+/// assert!(dir.join("src/nested").is_dir());
+/// ```
+pub fn archive<P: AsRef<Path>, Q: AsRef<Path>>(dir: P, out: Q) {
+    let mut entries = Vec::new();
+    collect(dir.as_ref(),"",&mut entries);
+    let mut f = io::BufWriter::new(::create(out));
+    for (name,path) in entries {
+        let name_bytes = name.as_bytes();
+        let is_dir = path.is_none();
+        quit!(f.write_all(if is_dir {b"d"} else {b"f"}));
+        quit!(f.write_all(&(name_bytes.len() as u32).to_le_bytes()));
+        quit!(f.write_all(name_bytes));
+        if let Some(path) = path {
+            let mut contents = Vec::new();
+            quit!(::open(&path).read_to_end(&mut contents));
+            quit!(f.write_all(&(contents.len() as u64).to_le_bytes()));
+            quit!(f.write_all(&contents));
+        }
+    }
+    quit!(f.flush());
+}
+
+/// restore an archive made by `archive` into the directory `dir`,
+/// which is created if needed. Quits on any i/o error, or if
+/// the archive is corrupt.
+pub fn unarchive<P: AsRef<Path>, Q: AsRef<Path>>(file: P, dir: Q) {
+    let dir = dir.as_ref();
+    let mut f = io::BufReader::new(::open(&file));
+    quit!(fs::create_dir_all(dir));
+    let mut kind = [0u8; 1];
+    loop {
+        if quit!(f.read(&mut kind)) == 0 {
+            break;
+        }
+        let mut len = [0u8; 4];
+        f.read_exact(&mut len).or_die("corrupt archive:");
+        let len = u64::from(u32::from_le_bytes(len));
+        // the length is not trusted, so only read what is really there
+        let mut name = Vec::new();
+        if quit!((&mut f).take(len).read_to_end(&mut name)) as u64 != len {
+            ::quit("corrupt archive: truncated name");
+        }
+        let name = String::from_utf8(name).or_die("corrupt archive:");
+        let rel = Path::new(&name);
+        if ! rel.components().all(|c| matches!(c,Component::Normal(_))) {
+            ::quit(&format!("unsafe path in archive: {}",name));
+        }
+        let path = dir.join(rel);
+        match kind[0] {
+            b'd' => quit!(fs::create_dir_all(&path)),
+            b'f' => {
+                let mut len = [0u8; 8];
+                f.read_exact(&mut len).or_die("corrupt archive:");
+                let len = u64::from_le_bytes(len);
+                if let Some(parent) = path.parent() {
+                    quit!(fs::create_dir_all(parent));
+                }
+                let mut out = io::BufWriter::new(::create(&path));
+                if quit!(io::copy(&mut (&mut f).take(len),&mut out)) != len {
+                    ::quit(&format!("corrupt archive: truncated contents of {}",name));
+                }
+                quit!(out.flush());
+            },
+            _ => ::quit("corrupt archive: bad record kind")
+        }
+    }
+}
+
+// relative names paired with the file path, or None for directories.
+// Sorted so that archives are reproducible.
+fn collect(dir: &Path, prefix: &str, entries: &mut Vec<(String,Option<PathBuf>)>) {
+    let mut children = ::paths(dir).to_vec();
+    children.sort_by(|a,b| a.0.cmp(&b.0));
+    for (path,meta) in children {
+        let file_name = path.file_name().unwrap();
+        let file_name = file_name.to_str()
+            .or_die_with(|| format!("cannot archive non-UTF-8 file name {:?}",file_name));
+        let name = format!("{}{}",prefix,file_name);
+        if meta.is_dir() {
+            entries.push((name.clone(),None));
+            collect(&path,&format!("{}/",name),entries);
+        } else {
+            entries.push((name,Some(path)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing::scratch_dir;
+
+    #[test]
+    fn test_round_trip() {
+        let tmp = scratch_dir("archive");
+        let root = tmp.path();
+        let src = root.join("src");
+        let dest = root.join("dest");
+        fs::create_dir_all(src.join("a/b/c")).unwrap();
+        fs::create_dir_all(src.join("empty")).unwrap();
+        ::write_all(src.join("top.txt"),"top level\n");
+        ::write_all(src.join("a/b/nested.txt"),"deeper");
+        ::write_all(src.join("a/zero"),"");
+        ::create(src.join("a/b/c/bin")).write_all(&[0,1,2,255]).unwrap();
+
+        let packed = root.join("packed");
+        archive(&src,&packed);
+        unarchive(&packed,&dest);
+
+        assert_eq!(::read_to_string(dest.join("top.txt")),"top level\n");
+        assert_eq!(::read_to_string(dest.join("a/b/nested.txt")),"deeper");
+        assert!(fs::metadata(dest.join("a/zero")).unwrap().len() == 0);
+        assert_eq!(fs::read(dest.join("a/b/c/bin")).unwrap(),vec![0,1,2,255]);
+        assert!(fs::metadata(dest.join("empty")).is_dir());
+
+        // and archiving the copy gives exactly the same archive
+        archive(&dest,root.join("again"));
+        assert_eq!(fs::read(&packed).unwrap(),fs::read(root.join("again")).unwrap());
+    }
+}
@@ -0,0 +1,163 @@
+//! Shortcuts for poking around Cargo projects.
+
+use std::env;
+use std::fs;
+use std::path::{Path,PathBuf};
+use traits::*;
+
+/// look up a simple string value in a TOML file like `Cargo.toml`.
+/// This is deliberately minimal: it finds the `[table]` header and
+/// then a `key = "value"` line in that table, returning the unquoted value.
+/// Arrays, inline tables, dotted keys and multi-line strings are not handled,
+/// and values that are not quoted strings give `None`, as does an unreadable file.
+///
+/// ```
+/// extern crate easy_shortcuts as es;
+///
+/// let name = es::toml_get("Cargo.toml","package","name");
+/// assert_eq!(name,Some("easy-shortcuts".to_string()));
+/// ```
+pub fn toml_get<P: AsRef<Path>>(file: P, table: &str, key: &str) -> Option<String> {
+    let text = fs::read_to_string(file).ok()?;
+    let mut in_table = false;
+    for line in text.lines() {
+        let line = line.trim();
+        if let Some(name) = table_name(line) {
+            in_table = name == table;
+        } else if in_table {
+            if let Some((k,v)) = line.split_at_delim('=') {
+                if k.trim() == key {
+                    return unquote(v.trim());
+                }
+            }
+        }
+    }
+    None
+}
+
+/// the crate name from the `Cargo.toml` in a directory, in the
+/// underscore form used by `extern crate`. `None` if there is no
+/// manifest or it has no `[package]` name.
+///
+/// ```
+/// extern crate easy_shortcuts as es;
+///
+/// assert_eq!(es::crate_name_from_manifest("."),Some("easy_shortcuts".to_string()));
+/// ```
+pub fn crate_name_from_manifest<P: AsRef<Path>>(dir: P) -> Option<String> {
+    toml_get(dir.as_ref().join("Cargo.toml"),"package","name")
+        .map(|name| name.replace('-',"_"))
+}
+
+/// the directory where Cargo puts build artifacts. This is `CARGO_TARGET_DIR`
+/// if set, otherwise the `target` directory next to the workspace's `Cargo.toml`,
+/// found by looking upwards from the current directory. Quits if there is
+/// no `Cargo.toml`.
+pub fn cargo_target_dir() -> PathBuf {
+    let here = env::current_dir().or_die("current directory:");
+    target_dir_from(env::var("CARGO_TARGET_DIR").ok(),&here)
+        .or_die("cannot find Cargo.toml")
+}
+
+fn target_dir_from(var: Option<String>, dir: &Path) -> Option<PathBuf> {
+    if let Some(var) = var {
+        return Some(PathBuf::from(var));
+    }
+    let mut manifest = ::find_up(dir,"Cargo.toml")?;
+    // a workspace member builds into the workspace's target directory
+    let mut candidate = manifest.clone();
+    while let Some(up) = candidate.parent().and_then(Path::parent).and_then(|d| ::find_up(d,"Cargo.toml")) {
+        if toml_has_table(&up,"workspace") {
+            manifest = up.clone();
+        }
+        candidate = up;
+    }
+    Some(manifest.with_file_name("target"))
+}
+
+fn toml_has_table(file: &Path, table: &str) -> bool {
+    fs::read_to_string(file).unwrap_or_default().lines().any(|l| table_name(l) == Some(table))
+}
+
+// the name in a `[table]` header line, ignoring any trailing comment
+fn table_name(line: &str) -> Option<&str> {
+    let line = line.split('#').next().unwrap_or("").trim();
+    if line.starts_with('[') {
+        Some(line.trim_start_matches('[').trim_end_matches(']').trim())
+    } else {
+        None
+    }
+}
+
+fn unquote(s: &str) -> Option<String> {
+    let quote = s.chars().next()?;
+    if quote != '"' && quote != '\'' {
+        return None;
+    }
+    let rest = &s[1..];
+    rest.find(quote).map(|idx| rest[0..idx].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing::scratch_dir;
+
+    #[test]
+    fn test_toml_get() {
+        let tmp = scratch_dir("toml");
+        let file = tmp.path().join("test.toml");
+        ::write_all(&file,r#"
+# a comment
+[package]
+name = "bonzo-dog" # the crate name
+version='0.1.0'
+edition = 2018
+
+[dependencies] # what we need
+version = "1.0"
+
+[ package.metadata ]
+name = "other"
+"#);
+        assert_eq!(toml_get(&file,"package","name"),Some("bonzo-dog".to_string()));
+        assert_eq!(toml_get(&file,"package","version"),Some("0.1.0".to_string()));
+        assert_eq!(toml_get(&file,"dependencies","version"),Some("1.0".to_string()));
+        assert_eq!(toml_get(&file,"package.metadata","name"),Some("other".to_string()));
+        assert_eq!(toml_get(&file,"package","edition"),None);
+        assert_eq!(toml_get(&file,"package","authors"),None);
+        assert_eq!(toml_get(&file,"nope","name"),None);
+
+        // comments after a header are allowed
+        ::write_all(&file,"[package] # main\nname = \"commented\"\n");
+        assert_eq!(toml_get(&file,"package","name"),Some("commented".to_string()));
+        fs::remove_file(&file).unwrap();
+        assert_eq!(toml_get(&file,"package","name"),None);
+    }
+
+    #[test]
+    fn test_crate_name_from_manifest() {
+        let tmp = scratch_dir("manifest");
+        let dir = tmp.path();
+        assert_eq!(crate_name_from_manifest(dir),None);
+        ::write_all(dir.join("Cargo.toml"),"[package]\nname = \"my-real-crate\"\n");
+        assert_eq!(crate_name_from_manifest(dir),Some("my_real_crate".to_string()));
+    }
+
+    #[test]
+    fn test_target_dir() {
+        let tmp = scratch_dir("target");
+        let root = tmp.path();
+        let member = root.join("member");
+        fs::create_dir_all(member.join("src")).unwrap();
+        ::write_all(member.join("Cargo.toml"),"[package]\nname = \"member\"\n");
+
+        // the env var always wins
+        let var = Some("/elsewhere/target".to_string());
+        assert_eq!(target_dir_from(var,&member),Some(PathBuf::from("/elsewhere/target")));
+
+        assert_eq!(target_dir_from(None,&member.join("src")),Some(member.join("target")));
+        ::write_all(root.join("Cargo.toml"),"[workspace] # all of them\nmembers = [\"member\"]\n");
+        assert_eq!(target_dir_from(None,&member.join("src")),Some(root.join("target")));
+    }
+}
@@ -0,0 +1,315 @@
+//! A very small command-line flag parser.
+//!
+//! Flags are declared with a short and a long form; boolean flags
+//! with `flag` and flags taking a value with `option`. Option values
+//! can be given as `--output file` or `--output=file`; `--output=` gives
+//! an empty value. Short flags can be bundled, so `-vq` means `-v -q`,
+//! and the last flag in a bundle may take a value from the next argument,
+//! as in `-vo file`. Anything that isn't a flag is collected as a
+//! positional argument, as is everything after `--`.
+//!
+//! Each flag can be given a description with `help`, which is
+//! used by `usage` to build the help text.
+//!
+//! ```
+//! use easy_shortcuts::Flags;
+//!
+//! let flags = Flags::new()
+//!     .flag("-v","--verbose")
+//!     .option("-o","--output");
+//! let args = flags.parse_args(["-v","in.txt","--output=out.txt"]).unwrap();
+//! assert!(args.has("verbose"));
+//! assert_eq!(args.option("output"),Some("out.txt"));
+//! assert_eq!(args.positional(),&["in.txt"]);
+//! ```
+
+use std::collections::HashMap;
+use std::env;
+use traits::*;
+
+#[derive(Debug,Clone)]
+struct Spec {
+    short: String,
+    long: String,
+    takes_value: bool,
+    help: String,
+}
+
+impl Spec {
+    // the long name without dashes identifies the flag
+    fn name(&self) -> &str {
+        self.long.trim_start_matches('-')
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        name == self.short || name == self.long || name == self.name()
+    }
+}
+
+/// declares the flags and options a program accepts.
+#[derive(Debug,Clone,Default)]
+pub struct Flags {
+    specs: Vec<Spec>,
+}
+
+/// the result of parsing the command line with `Flags`.
+#[derive(Debug,Clone)]
+pub struct Args {
+    specs: Vec<Spec>,
+    set: HashMap<String,String>,
+    positional: Vec<String>,
+}
+
+impl Flags {
+    /// a parser with no flags declared.
+    pub fn new() -> Flags {
+        Flags::default()
+    }
+
+    fn add(mut self, short: &str, long: &str, takes_value: bool) -> Flags {
+        self.specs.push(Spec{
+            short: short.to_string(), long: long.to_string(),
+            takes_value, help: String::new()
+        });
+        self
+    }
+
+    /// declare a boolean flag, like `flag("-v","--verbose")`.
+    pub fn flag(self, short: &str, long: &str) -> Flags {
+        self.add(short,long,false)
+    }
+
+    /// declare a flag which takes a value, like `option("-o","--output")`.
+    pub fn option(self, short: &str, long: &str) -> Flags {
+        self.add(short,long,true)
+    }
+
+    /// describe the last flag declared, for the usage text.
+    ///
+    /// ```
+    /// use easy_shortcuts::Flags;
+    ///
+    /// let flags = Flags::new()
+    ///     .flag("-v","--verbose").help("say more")
+    ///     .option("-o","--output").help("write here");
+    /// assert_eq!(flags.usage("prog"),"\
+    /// usage: prog [flags] [args]
+    ///
+    ///   -v, --verbose         say more
+    ///   -o, --output <value>  write here
+    /// ");
+    /// ```
+    pub fn help(mut self, text: &str) -> Flags {
+        if let Some(spec) = self.specs.last_mut() {
+            spec.help = text.to_string();
+        }
+        self
+    }
+
+    /// a conventional usage message listing the declared flags
+    /// and their descriptions, aligned in columns.
+    pub fn usage(&self, prog: &str) -> String {
+        let names = self.specs.iter().map(|s| {
+            let value = if s.takes_value {" <value>"} else {""};
+            format!("{}, {}{}",s.short,s.long,value)
+        }).to_vec();
+        let width = names.iter().map(|n| n.display_width()).max().unwrap_or(0);
+        let mut res = format!("usage: {} [flags] [args]\n",prog);
+        if ! self.specs.is_empty() {
+            res.push('\n');
+        }
+        for (name,spec) in names.iter().zip(&self.specs) {
+            res += format!("  {}  {}",name.pad_right(width),spec.help).trim_end();
+            res.push('\n');
+        }
+        res
+    }
+
+    /// parse the program's command-line arguments. If there is an
+    /// unknown flag or a missing value, the error and the usage text
+    /// are printed and the program exits with code 2.
+    pub fn parse(&self) -> Args {
+        match self.parse_args(env::args().skip(1)) {
+            Ok(args) => args,
+            Err(e) => {
+                let prog = ::argn_or(0,"");
+                ::usage(&format!("{} error: {}\n{}",prog,e,self.usage(&prog)))
+            }
+        }
+    }
+
+    /// parse the given arguments, which should not include the program name.
+    pub fn parse_args<I,S>(&self, args: I) -> Result<Args,String>
+    where I: IntoIterator<Item=S>, S: AsRef<str> {
+        let mut res = Args{specs: self.specs.clone(), set: HashMap::new(), positional: Vec::new()};
+        let mut iter = args.into_iter().map(|s| s.as_ref().to_string());
+        while let Some(arg) = iter.next() {
+            if arg == "--" {
+                res.positional.extend(iter.by_ref());
+                break;
+            }
+            if ! arg.starts_with('-') || arg == "-" {
+                res.positional.push(arg);
+                continue;
+            }
+            let (name,value) = match arg.split_at_delim('=') {
+                Some((name,value)) if arg.starts_with("--") => (name.to_string(),Some(value.to_string())),
+                _ => (arg.clone(),None)
+            };
+            let spec = match self.specs.iter().find(|s| s.short == name || s.long == name) {
+                Some(spec) => spec,
+                None if ! name.starts_with("--") => {
+                    self.parse_bundle(&name,&mut iter,&mut res)?;
+                    continue;
+                },
+                None => return Err(format!("unknown flag {}",name))
+            };
+            let value = if spec.takes_value {
+                match value.or_else(|| iter.next()) {
+                    Some(v) => v,
+                    None => return Err(format!("flag {} needs a value",name))
+                }
+            } else if value.is_some() {
+                return Err(format!("flag {} does not take a value",name));
+            } else {
+                String::new()
+            };
+            res.set.insert(spec.name().to_string(),value);
+        }
+        Ok(res)
+    }
+
+    // short flags bundled together like `-vq`; only the last may take a value
+    fn parse_bundle<I>(&self, bundle: &str, rest: &mut I, res: &mut Args) -> Result<(),String>
+    where I: Iterator<Item=String> {
+        let letters = bundle[1..].chars().to_vec();
+        for (i,c) in letters.iter().enumerate() {
+            let name = format!("-{}",c);
+            let spec = match self.specs.iter().find(|s| s.short == name) {
+                Some(spec) => spec,
+                None if i == 0 => return Err(format!("unknown flag {}",bundle)),
+                None => return Err(format!("unknown flag {} in {}",name,bundle))
+            };
+            let value = if ! spec.takes_value {
+                String::new()
+            } else if i + 1 < letters.len() {
+                return Err(format!("flag {} needs a value, so must come last in {}",name,bundle));
+            } else {
+                match rest.next() {
+                    Some(v) => v,
+                    None => return Err(format!("flag {} needs a value",name))
+                }
+            };
+            res.set.insert(spec.name().to_string(),value);
+        }
+        Ok(())
+    }
+}
+
+impl Args {
+    fn lookup(&self, name: &str) -> Option<&String> {
+        self.specs.iter()
+            .find(|s| s.matches(name))
+            .and_then(|s| self.set.get(s.name()))
+    }
+
+    /// was this flag given? The name can be the short or long form,
+    /// or the long form without dashes.
+    pub fn has(&self, name: &str) -> bool {
+        self.lookup(name).is_some()
+    }
+
+    /// the value of an option, if it was given.
+    pub fn option(&self, name: &str) -> Option<&str> {
+        self.lookup(name).map(|s| s.as_str())
+    }
+
+    /// the arguments which were not flags.
+    pub fn positional(&self) -> &[String] {
+        &self.positional
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flags() -> Flags {
+        Flags::new()
+            .flag("-v","--verbose")
+            .flag("-q","--quiet")
+            .option("-o","--output")
+    }
+
+    #[test]
+    fn test_flags() {
+        let args = flags().parse_args(["-v","one","--quiet","two"]).unwrap();
+        assert!(args.has("verbose"));
+        assert!(args.has("-v"));
+        assert!(args.has("--quiet"));
+        assert_eq!(args.option("output"),None);
+        assert_eq!(args.positional(),&["one","two"]);
+
+        let args = flags().parse_args(Vec::<String>::new()).unwrap();
+        assert!(! args.has("verbose"));
+        assert!(args.positional().is_empty());
+    }
+
+    #[test]
+    fn test_options() {
+        let args = flags().parse_args(["-o","out.txt","in.txt"]).unwrap();
+        assert_eq!(args.option("-o"),Some("out.txt"));
+        assert_eq!(args.positional(),&["in.txt"]);
+
+        let args = flags().parse_args(["--output=a=b","--","-v","-"]).unwrap();
+        assert_eq!(args.option("output"),Some("a=b"));
+        assert!(! args.has("verbose"));
+        assert_eq!(args.positional(),&["-v","-"]);
+    }
+
+    #[test]
+    fn test_value_forms() {
+        for args in &[vec!["--output","x"],vec!["--output=x"],vec!["-o","x"],vec!["-vo","x"]] {
+            let parsed = flags().parse_args(args).unwrap();
+            assert_eq!(parsed.option("output"),Some("x"),"{:?}",args);
+            assert!(parsed.positional().is_empty());
+        }
+        let args = flags().parse_args(["--output="]).unwrap();
+        assert_eq!(args.option("output"),Some(""));
+    }
+
+    #[test]
+    fn test_bundled_flags() {
+        let args = flags().parse_args(["-vq","file"]).unwrap();
+        assert!(args.has("verbose") && args.has("quiet"));
+        assert_eq!(args.positional(),&["file"]);
+
+        let args = flags().parse_args(["-qvo","out.txt","in.txt"]).unwrap();
+        assert!(args.has("verbose") && args.has("quiet"));
+        assert_eq!(args.option("output"),Some("out.txt"));
+        assert_eq!(args.positional(),&["in.txt"]);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(flags().parse_args(["-x"]).unwrap_err(),"unknown flag -x");
+        assert_eq!(flags().parse_args(["-vx"]).unwrap_err(),"unknown flag -x in -vx");
+        assert_eq!(flags().parse_args(["-xv"]).unwrap_err(),"unknown flag -xv");
+        assert_eq!(flags().parse_args(["-ov","out"]).unwrap_err(),"flag -o needs a value, so must come last in -ov");
+        assert_eq!(flags().parse_args(["-vo"]).unwrap_err(),"flag -o needs a value");
+        assert_eq!(flags().parse_args(["--output"]).unwrap_err(),"flag --output needs a value");
+        assert_eq!(flags().parse_args(["--verbose=1"]).unwrap_err(),"flag --verbose does not take a value");
+    }
+
+    #[test]
+    fn test_usage() {
+        let text = flags()
+            .flag("-x","--extra").help("extra things")
+            .usage("prog");
+        assert!(text.starts_with("usage: prog"));
+        for line in &["-v, --verbose","-q, --quiet","-o, --output <value>","-x, --extra","extra things"] {
+            assert!(text.contains(line),"{} missing from {}",line,text);
+        }
+        assert_eq!(Flags::new().usage("prog"),"usage: prog [flags] [args]\n");
+    }
+}
@@ -0,0 +1,129 @@
+//! Reading and writing INI-style configuration files.
+//!
+//! These have `[section]` headers followed by `key=value` lines.
+//! Blank lines and lines starting with `;` or `#` are ignored,
+//! and any keys before the first header go into the `""` section.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use traits::*;
+
+/// the sections of an INI file, each being a map of keys to values.
+pub type IniMap = BTreeMap<String,BTreeMap<String,String>>;
+
+/// read an INI file into a map of sections,
+/// quitting if it can't be read or has a malformed line.
+///
+/// ```
+/// extern crate easy_shortcuts as es;
+/// # let file = std::env::temp_dir().join("easy-shortcuts-doc.ini");
+/// # es::write_all(&file,"name=demo\n[server]\nport = 8080\n");
+///
+/// let ini = es::read_ini(&file);
+/// assert_eq!(ini[""]["name"],"demo");
+/// assert_eq!(ini["server"]["port"],"8080");
+/// ```
+pub fn read_ini<P: AsRef<Path>>(file: P) -> IniMap {
+    let text = ::read_to_string(&file);
+    parse_ini(&text).or_then_die(|e| format!("{:?}:{}",file.as_ref(),e))
+}
+
+/// write a map of sections out as an INI file, quitting on error.
+/// Keys in the `""` section come first, without a header;
+/// sections and keys are written in sorted order.
+pub fn write_ini<P: AsRef<Path>>(file: P, data: &IniMap) {
+    ::write_all(file,&format_ini(data));
+}
+
+fn format_ini(data: &IniMap) -> String {
+    let mut res = String::new();
+    if let Some(keys) = data.get("") {
+        for (k,v) in keys {
+            res += &format!("{}={}\n",k,v);
+        }
+    }
+    for (section,keys) in data.iter().filter(|&(s,_)| ! s.is_empty()) {
+        if ! res.is_empty() {
+            res.push('\n');
+        }
+        res += &format!("[{}]\n",section);
+        for (k,v) in keys {
+            res += &format!("{}={}\n",k,v);
+        }
+    }
+    res
+}
+
+fn parse_ini(text: &str) -> Result<IniMap,String> {
+    let mut res = IniMap::new();
+    let mut section = String::new();
+    for (i,line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('[') {
+            // a comment may follow the header
+            let line = line.split([';','#']).next().unwrap_or("").trim_end();
+            if ! line.ends_with(']') {
+                return Err(format!("{}: unterminated section header",i+1));
+            }
+            section = line[1..line.len()-1].trim().to_string();
+            res.entry(section.clone()).or_default();
+        } else if let Some((key,val)) = line.split_at_delim('=').trim() {
+            res.entry(section.clone()).or_default().insert(key,val);
+        } else {
+            return Err(format!("{}: expected key=value",i+1));
+        }
+    }
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sections() {
+        let ini = parse_ini("
+; a comment
+top = 1
+
+[one]
+# another comment
+a = hello world
+b=2
+[ two ]
+a =
+[server] ; main
+port = 80
+[client]# the other end
+").unwrap();
+        assert_eq!(ini.len(),5);
+        assert_eq!(ini[""]["top"],"1");
+        assert_eq!(ini["one"]["a"],"hello world");
+        assert_eq!(ini["one"]["b"],"2");
+        assert_eq!(ini["two"]["a"],"");
+        assert_eq!(ini["server"]["port"],"80");
+        assert!(ini["client"].is_empty());
+    }
+
+    #[test]
+    fn test_malformed() {
+        assert_eq!(parse_ini("[one]\na=1\nbonzo\n"),Err("3: expected key=value".to_string()));
+        assert_eq!(parse_ini("[one\n"),Err("1: unterminated section header".to_string()));
+        assert_eq!(parse_ini("[one ; two]\n"),Err("1: unterminated section header".to_string()));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let text = "b=2\na=1\n[zed]\nx = 10\n[alpha]\ny=\n";
+        let ini = parse_ini(text).unwrap();
+        assert_eq!(format_ini(&ini),"a=1\nb=2\n\n[alpha]\ny=\n\n[zed]\nx=10\n");
+
+        let tmp = ::testing::scratch_dir("ini");
+        let file = tmp.path().join("test.ini");
+        write_ini(&file,&ini);
+        assert_eq!(read_ini(&file),ini);
+    }
+}
@@ -0,0 +1,411 @@
+//! A minimal JSON reader and writer for small configuration files.
+//!
+//! This is not meant to replace serde; it only handles well-formed
+//! JSON, and errors report the byte position where parsing failed.
+//! Values are written back out with `to_string` or `to_string_pretty`;
+//! object keys always come out in sorted order.
+//!
+//! ```
+//! use easy_shortcuts::json::{parse_json,JsonValue};
+//!
+//! let v = parse_json(r#"{"name":"bonzo","tags":[1,2]}"#).unwrap();
+//! if let JsonValue::Object(ref m) = v {
+//!     assert_eq!(m["name"],JsonValue::String("bonzo".to_string()));
+//! }
+//! assert_eq!(v.to_string(),r#"{"name":"bonzo","tags":[1,2]}"#);
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::Chars;
+use std::iter::Peekable;
+
+/// a parsed JSON value. Objects use a `BTreeMap`
+/// so that keys come out in a stable order.
+#[derive(Debug,Clone,PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(BTreeMap<String,JsonValue>),
+}
+
+/// escape a string so that it can be written as a JSON string literal.
+/// The surrounding quotes are not included.
+///
+/// ```
+/// use easy_shortcuts::json::json_escape;
+///
+/// assert_eq!(json_escape("a \"b\"\n"),r#"a \"b\"\n"#);
+/// ```
+pub fn json_escape(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            '\n' => res.push_str("\\n"),
+            '\r' => res.push_str("\\r"),
+            '\t' => res.push_str("\\t"),
+            c if (c as u32) < 0x20 => res.push_str(&format!("\\u{:04x}",c as u32)),
+            c => res.push(c)
+        }
+    }
+    res
+}
+
+impl JsonValue {
+    /// write out this value as indented JSON, using `indent`
+    /// spaces for each level of nesting.
+    ///
+    /// ```
+    /// use easy_shortcuts::json::parse_json;
+    ///
+    /// let v = parse_json(r#"{"a":[1,2]}"#).unwrap();
+    /// assert_eq!(v.to_string_pretty(2),"{\n  \"a\": [\n    1,\n    2\n  ]\n}");
+    /// ```
+    pub fn to_string_pretty(&self, indent: usize) -> String {
+        let mut res = String::new();
+        self.write_pretty(&mut res,indent,0);
+        res
+    }
+
+    fn write_pretty(&self, res: &mut String, indent: usize, level: usize) {
+        let outer = " ".repeat(indent*level);
+        let inner = " ".repeat(indent*(level+1));
+        match *self {
+            JsonValue::Array(ref arr) if ! arr.is_empty() => {
+                res.push_str("[\n");
+                for (i,v) in arr.iter().enumerate() {
+                    if i > 0 {
+                        res.push_str(",\n");
+                    }
+                    res.push_str(&inner);
+                    v.write_pretty(res,indent,level+1);
+                }
+                res.push('\n');
+                res.push_str(&outer);
+                res.push(']');
+            },
+            JsonValue::Object(ref map) if ! map.is_empty() => {
+                res.push_str("{\n");
+                for (i,(k,v)) in map.iter().enumerate() {
+                    if i > 0 {
+                        res.push_str(",\n");
+                    }
+                    res.push_str(&format!("{}\"{}\": ",inner,json_escape(k)));
+                    v.write_pretty(res,indent,level+1);
+                }
+                res.push('\n');
+                res.push_str(&outer);
+                res.push('}');
+            },
+            ref v => res.push_str(&v.to_string())
+        }
+    }
+}
+
+/// compact JSON output, so that `to_string` gives the JSON text.
+impl fmt::Display for JsonValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            JsonValue::Null => write!(f,"null"),
+            JsonValue::Bool(b) => write!(f,"{}",b),
+            // JSON has no representation for NaN or infinity
+            JsonValue::Number(x) if ! x.is_finite() => write!(f,"null"),
+            JsonValue::Number(x) => write!(f,"{}",x),
+            JsonValue::String(ref s) => write!(f,"\"{}\"",json_escape(s)),
+            JsonValue::Array(ref arr) => {
+                write!(f,"[")?;
+                for (i,v) in arr.iter().enumerate() {
+                    if i > 0 {
+                        write!(f,",")?;
+                    }
+                    write!(f,"{}",v)?;
+                }
+                write!(f,"]")
+            },
+            JsonValue::Object(ref map) => {
+                write!(f,"{{")?;
+                for (i,(k,v)) in map.iter().enumerate() {
+                    if i > 0 {
+                        write!(f,",")?;
+                    }
+                    write!(f,"\"{}\":{}",json_escape(k),v)?;
+                }
+                write!(f,"}}")
+            }
+        }
+    }
+}
+
+/// parse a JSON document into a `JsonValue`.
+/// The error message contains the byte position of the problem.
+pub fn parse_json(s: &str) -> Result<JsonValue,String> {
+    let mut p = Parser{chars: s.chars().peekable(), pos: 0};
+    let res = p.value()?;
+    p.skip_whitespace();
+    if p.peek().is_some() {
+        return p.error("end of input");
+    }
+    Ok(res)
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+    pos: usize,
+}
+
+impl <'a> Parser<'a> {
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().cloned()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next();
+        if let Some(c) = c {
+            self.pos += c.len_utf8();
+        }
+        c
+    }
+
+    fn error<T>(&self, expected: &str) -> Result<T,String> {
+        Err(format!("expected {} at position {}",expected,self.pos))
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if ! c.is_whitespace() {
+                break;
+            }
+            self.next();
+        }
+    }
+
+    fn expect(&mut self, ch: char) -> Result<(),String> {
+        if self.peek() == Some(ch) {
+            self.next();
+            Ok(())
+        } else {
+            self.error(&format!("'{}'",ch))
+        }
+    }
+
+    fn literal(&mut self, word: &str, val: JsonValue) -> Result<JsonValue,String> {
+        for ch in word.chars() {
+            if self.peek() != Some(ch) {
+                return self.error(word);
+            }
+            self.next();
+        }
+        Ok(val)
+    }
+
+    fn value(&mut self) -> Result<JsonValue,String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('n') => self.literal("null",JsonValue::Null),
+            Some('t') => self.literal("true",JsonValue::Bool(true)),
+            Some('f') => self.literal("false",JsonValue::Bool(false)),
+            Some('"') => Ok(JsonValue::String(self.string()?)),
+            Some('[') => self.array(),
+            Some('{') => self.object(),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.number(),
+            _ => self.error("value")
+        }
+    }
+
+    fn number(&mut self) -> Result<JsonValue,String> {
+        let start = self.pos;
+        let mut text = String::new();
+        while let Some(c) = self.peek() {
+            if c.is_ascii_digit() || c == '-' || c == '+' || c == '.' || c == 'e' || c == 'E' {
+                text.push(c);
+                self.next();
+            } else {
+                break;
+            }
+        }
+        match text.parse::<f64>() {
+            Ok(x) => Ok(JsonValue::Number(x)),
+            Err(_) => Err(format!("expected number at position {}",start))
+        }
+    }
+
+    fn hex4(&mut self) -> Result<u32,String> {
+        let mut code = 0;
+        for _ in 0..4 {
+            match self.peek().and_then(|c| c.to_digit(16)) {
+                Some(d) => { code = code*16 + d; self.next(); },
+                None => return self.error("hex digit")
+            }
+        }
+        Ok(code)
+    }
+
+    fn string(&mut self) -> Result<String,String> {
+        self.expect('"')?;
+        let mut res = String::new();
+        loop {
+            match self.next() {
+                Some('"') => return Ok(res),
+                Some('\\') => {
+                    let ch = match self.next() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => {
+                            let mut code = self.hex4()?;
+                            // a surrogate pair is written as two escapes
+                            if (0xD800..0xDC00).contains(&code) {
+                                self.expect('\\')?;
+                                self.expect('u')?;
+                                let start = self.pos;
+                                let low = self.hex4()?;
+                                if ! (0xDC00..0xE000).contains(&low) {
+                                    return Err(format!("expected low surrogate at position {}",start));
+                                }
+                                code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+                            }
+                            match ::std::char::from_u32(code) {
+                                Some(c) => c,
+                                None => return self.error("valid unicode escape")
+                            }
+                        },
+                        _ => return self.error("escape")
+                    };
+                    res.push(ch);
+                },
+                Some(c) => res.push(c),
+                None => return self.error("'\"'")
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<JsonValue,String> {
+        self.expect('[')?;
+        let mut res = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.next();
+            return Ok(JsonValue::Array(res));
+        }
+        loop {
+            res.push(self.value()?);
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some(']') => return Ok(JsonValue::Array(res)),
+                _ => return self.error("',' or ']'")
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<JsonValue,String> {
+        self.expect('{')?;
+        let mut res = BTreeMap::new();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.next();
+            return Ok(JsonValue::Object(res));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.skip_whitespace();
+            self.expect(':')?;
+            let val = self.value()?;
+            res.insert(key,val);
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some('}') => return Ok(JsonValue::Object(res)),
+                _ => return self.error("',' or '}'")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nested() {
+        let v = parse_json(r#" { "a": {"b": [1, 2.5, -3e2]}, "c": null, "d": true } "#).unwrap();
+        let mut inner = BTreeMap::new();
+        inner.insert("b".to_string(), JsonValue::Array(vec![
+            JsonValue::Number(1.0), JsonValue::Number(2.5), JsonValue::Number(-300.0)
+        ]));
+        let mut outer = BTreeMap::new();
+        outer.insert("a".to_string(), JsonValue::Object(inner));
+        outer.insert("c".to_string(), JsonValue::Null);
+        outer.insert("d".to_string(), JsonValue::Bool(true));
+        assert_eq!(v,JsonValue::Object(outer));
+    }
+
+    #[test]
+    fn test_arrays() {
+        assert_eq!(parse_json("[]").unwrap(),JsonValue::Array(vec![]));
+        let v = parse_json(r#"[[false],"x",{}]"#).unwrap();
+        assert_eq!(v,JsonValue::Array(vec![
+            JsonValue::Array(vec![JsonValue::Bool(false)]),
+            JsonValue::String("x".to_string()),
+            JsonValue::Object(BTreeMap::new())
+        ]));
+    }
+
+    #[test]
+    fn test_escapes() {
+        let v = parse_json(r#""a\"b\\c\/\n\té😀""#).unwrap();
+        assert_eq!(v,JsonValue::String("a\"b\\c/\n\té😀".to_string()));
+        assert_eq!(parse_json(r#""\uD83D\uDE00""#).unwrap(),JsonValue::String("😀".to_string()));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(parse_json(r#"{"a" 1}"#),Err("expected ':' at position 5".to_string()));
+        assert_eq!(parse_json("[1,2"),Err("expected ',' or ']' at position 4".to_string()));
+        assert_eq!(parse_json("tru"),Err("expected true at position 3".to_string()));
+        assert_eq!(parse_json("1 2"),Err("expected end of input at position 2".to_string()));
+        assert_eq!(parse_json(r#""\uD83D\u0041""#),Err("expected low surrogate at position 9".to_string()));
+        assert_eq!(parse_json(r#""\uD83D\uD83D""#),Err("expected low surrogate at position 9".to_string()));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let docs = [
+            "null", "true", "-1.5", r#""tab\there \"quoted\" \u0001""#, "[]", "{}",
+            r#"{"b":[1,{"c":null}],"a":"x","z":{"y":false}}"#,
+        ];
+        for doc in docs.iter() {
+            let v = parse_json(doc).unwrap();
+            assert_eq!(parse_json(&v.to_string()).unwrap(),v);
+            assert_eq!(parse_json(&v.to_string_pretty(4)).unwrap(),v);
+        }
+        let v = parse_json(r#"{"b":1,"a":[true,"x"]}"#).unwrap();
+        assert_eq!(v.to_string(),r#"{"a":[true,"x"],"b":1}"#);
+    }
+
+    #[test]
+    fn test_pretty() {
+        let v = parse_json(r#"{"b":{"c":[]},"a":[1,"two"]}"#).unwrap();
+        let expected = r#"{
+  "a": [
+    1,
+    "two"
+  ],
+  "b": {
+    "c": []
+  }
+}"#;
+        assert_eq!(v.to_string_pretty(2),expected);
+    }
+}
@@ -12,7 +12,14 @@
 //! `read_to_string` and `write_all` to read and write the contents of
 //! text files, and `lines` provides a straight iterator over all lines
 //! in a readable.  `paths` provides an iterator over `(PathBuf,Metadata)`
-//! pairs in a directory
+//! pairs in a directory, and `all_paths` does the same for a whole directory tree.
+//!
+//! `parse_json` reads small JSON documents into a `JsonValue`,
+//! without needing any dependencies.
+//!
+//! `read_ini` and `write_ini` handle INI-style files with `[section]` headers.
+//!
+//! `Bar` shows a progress bar on standard error for long-running work.
 //!
 //! The `or_die` method is implemented for option and result types, allowing
 //! a Perl-style equivalent to the usual `expect`.
@@ -39,16 +46,25 @@
 //! assert_eq!(s," -Lone -Ltwo -Lthree");
 //! ```
 
+#[cfg(feature = "unicode")]
+extern crate unicode_width;
+
 use std::io;
 use std::fs::File;
 use std::process::Command;
 use std::io::prelude::*;
 use std::fmt::{Display,Debug};
 use std::collections::HashMap;
+use std::time::{SystemTime,Duration};
+use std::cell::{Cell,RefCell};
+use std::sync::{Arc,Mutex,OnceLock};
 
 
 pub mod traits {
     use std::collections::HashMap;
+    use std::hash::Hash;
+    use std::io::Write;
+    use std::time::SystemTime;
 
     /// convenient to_vec() method on iterators
     pub trait ToVec<T> {
@@ -70,10 +86,39 @@ pub mod traits {
     pub trait ToMap<K,V> {
         /// collect values into a HashMap
         fn to_map(self) -> HashMap<K,V>;
+
+        /// collect values into a HashMap, combining the values
+        /// of duplicate keys with `merge(existing,new)` rather
+        /// than overwriting them.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::ToMap;
+        ///
+        /// let pairs = vec![("a",1),("b",2),("a",3)];
+        /// let m = pairs.into_iter().to_map_merge(|x,y| x + y);
+        /// assert_eq!(m["a"],4);
+        /// assert_eq!(m["b"],2);
+        /// ```
+        fn to_map_merge<F: Fn(V,V)->V>(self, merge: F) -> HashMap<K,V>;
+    }
+
+    /// counting values on iterators
+    pub trait CountBy<T> {
+        /// count the values by the key `key` gives for them, returning
+        /// (key,count) pairs with the most common first. Keys with the
+        /// same count are in ascending order.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::CountBy;
+        ///
+        /// let counts = "b a c a b a".split(' ').count_by_sorted(|s| s.to_string());
+        /// assert_eq!(counts[0],("a".to_string(),3));
+        /// ```
+        fn count_by_sorted<K: Eq + Hash + Ord, F: Fn(&T) -> K>(self, key: F) -> Vec<(K,usize)>;
     }
 
     /// string collection methods on iterators
-    pub trait Join {
+    pub trait Join: Iterator + Sized {
         /// Join an iterator of strings using a delimiter.
         ///
         /// ## Example
@@ -87,6 +132,99 @@ pub mod traits {
         /// ```
         fn join(self, delim: char) -> String;
 
+        /// Join an iterator of strings using a string delimiter,
+        /// which only goes between the values.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::Join;
+        ///
+        /// let s = ["a","b","c"].into_iter().join_str(" -> ");
+        /// assert_eq!(s,"a -> b -> c");
+        /// ```
+        fn join_str(self, delim: &str) -> String;
+
+        /// Concatenate an iterator of strings, with nothing between them.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::Join;
+        ///
+        /// assert_eq!("a b c".split(' ').concat_strings(),"abc");
+        /// ```
+        fn concat_strings(self) -> String;
+
+        /// Join like `join`, but add the result to the end of an
+        /// existing string, so that its buffer can be reused.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::Join;
+        ///
+        /// let mut buf = String::new();
+        /// for line in &["a b","c d e"] {
+        ///     buf.clear();
+        ///     line.split(' ').join_to(',',&mut buf);
+        ///     println!("{}",buf);
+        /// }
+        /// assert_eq!(buf,"c,d,e");
+        /// ```
+        fn join_to(self, delim: char, out: &mut String);
+
+        /// Join like `join`, skipping any empty strings.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::Join;
+        ///
+        /// assert_eq!("a,,b,".split(',').join_non_empty(','),"a,b");
+        /// ```
+        fn join_non_empty(self, delim: char) -> String;
+
+        /// Join like `join`, skipping any strings which are
+        /// empty or only whitespace.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::Join;
+        ///
+        /// assert_eq!("a, ,b".split(',').join_non_blank(';'),"a;b");
+        /// ```
+        fn join_non_blank(self, delim: char) -> String;
+
+        /// Join like `join`, putting each string in single quotes
+        /// so that it is one word to a POSIX shell. Any single quotes
+        /// inside are written as `'\''`.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::Join;
+        ///
+        /// let args = ["a b","c'd"].into_iter().quote_join(' ');
+        /// assert_eq!(args,r"'a b' 'c'\''d'");
+        /// ```
+        fn quote_join(self, delim: char) -> String;
+
+        /// Join fields into a line of CSV, without a line ending. Fields
+        /// containing a comma, double quote, CR or LF are put in double
+        /// quotes, with any quotes inside doubled, as in RFC 4180.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::Join;
+        ///
+        /// let line = ["a,b","c\"d","e"].into_iter().csv_line();
+        /// assert_eq!(line,r#""a,b","c""d",e"#);
+        /// ```
+        fn csv_line(self) -> String;
+
+        /// Put a separator between the values, without collecting them.
+        /// The separator only goes between values, never at the end.
+        /// Since the standard library has an unstable method of the
+        /// same name, call it as `Join::intersperse(iter,sep)`.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::*;
+        ///
+        /// let v = Join::intersperse("a b c".split_whitespace(),"-").to_vec();
+        /// assert_eq!(v,&["a","-","b","-","c"]);
+        /// ```
+        fn intersperse(self, sep: Self::Item) -> ::Intersperse<Self>
+        where Self::Item: Clone;
+
         /// Join an iterator of strings by prepending a prefix
         ///
         /// ```
@@ -97,14 +235,57 @@ pub mod traits {
         /// ```
         fn prepend(self, prefix: &str) -> String;
 
+        /// Join an iterator of strings, putting a prefix before
+        /// and a suffix after each one
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::Join;
+        ///
+        /// let s = ["a","b"].into_iter().wrap_each("<",">");
+        /// assert_eq!(s,"<a><b>");
+        /// ```
+        fn wrap_each(self, prefix: &str, suffix: &str) -> String;
+
         /// Join strings by collecting the result of an arbitrary function
         fn append<T: Fn(&str)->String>(self, map: T)->String;
+
+        /// Join strings like `append`, also passing the zero-based index
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::Join;
+        ///
+        /// let s = "one\ntwo".lines().append_indexed(|i,l| format!("{:3}: {}\n",i+1,l));
+        /// assert_eq!(s,"  1: one\n  2: two\n");
+        /// ```
+        fn append_indexed<T: Fn(usize,&str)->String>(self, map: T)->String;
+
+        /// Build a string by calling `f(&mut acc, item, is_first)` for
+        /// each string, so the caller decides how separators and items
+        /// are written.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::Join;
+        ///
+        /// let s = ["a","b","c"].into_iter().join_fold(|acc,item,first| {
+        ///     if ! first {
+        ///         acc.push(',');
+        ///     }
+        ///     acc.push_str(item);
+        /// });
+        /// assert_eq!(s,"a,b,c");
+        /// ```
+        fn join_fold<F: FnMut(&mut String,&str,bool)>(self, f: F) -> String;
     }
 
     /// provides a print() method over iterators
     pub trait Print {
         ///  Consume values that implement Display
         ///  and print them out to stdout with the given terminator.
+        ///  The output is buffered and flushed after the last value,
+        ///  so if it has to interleave with the output of a child
+        ///  process, print one value at a time. If a value quits
+        ///  partway through (say with `or_die`) the buffered output
+        ///  is flushed first, so nothing is lost.
         ///
         /// ```
         ///  use easy_shortcuts::traits::Print;
@@ -112,12 +293,78 @@ pub mod traits {
         ///  [10,20,30].into_iter().print("\n");
         /// ```
         fn print(self,delim: &str);
+
+        /// like `print`, but writes to `w`, which may be stderr,
+        /// an open file, or a `Vec<u8>`. Will quit on a write error.
+        ///
+        /// ```
+        ///  use easy_shortcuts::traits::Print;
+        ///
+        ///  let mut out = Vec::new();
+        ///  [10,20,30].into_iter().print_to(&mut out," ");
+        ///  assert_eq!(out,b"10 20 30 ");
+        /// ```
+        fn print_to<W: Write>(self, w: &mut W, delim: &str);
+
+        ///  like `print`, but to stderr, so that diagnostics
+        ///  don't get mixed up with the output proper.
+        ///
+        /// ```
+        ///  use easy_shortcuts::traits::Print;
+        ///
+        ///  ["warning: no files"].into_iter().eprint("\n");
+        /// ```
+        fn eprint(self,delim: &str);
+
+        /// like `print`, but also returns the values printed.
+        ///
+        /// ```
+        ///  use easy_shortcuts::traits::Print;
+        ///
+        ///  let v = (1..4).map(|n| n*10).print_vec(" ");
+        ///  assert_eq!(v,&[10,20,30]);
+        /// ```
+        fn print_vec(self,delim: &str) -> Vec<Self::Item>
+        where Self: Iterator + Sized;
+
+        /// like `print`, but returns how many values were printed.
+        ///
+        /// ```
+        ///  use easy_shortcuts::traits::Print;
+        ///
+        ///  let n = "one\ntwo\nthree".lines().print_count("\n");
+        ///  assert_eq!(n,3);
+        /// ```
+        fn print_count(self,delim: &str) -> usize;
+
+        /// print the values in aligned columns like `ls`, using as many
+        /// columns as fit in `term_width`. The values go down each column
+        /// in turn. A value too wide for the terminal gets a line to itself.
+        ///
+        /// ```
+        ///  use easy_shortcuts::traits::Print;
+        ///
+        ///  ["one","two","three","four"].into_iter().print_columns(20);
+        /// ```
+        fn print_columns(self,term_width: usize);
+
+        /// print each value on its own line, and if it is longer than
+        /// `width`, re-wrap it at word boundaries with `reflow`.
+        /// A word longer than the width is printed as it is.
+        ///
+        /// ```
+        ///  use easy_shortcuts::traits::Print;
+        ///
+        ///  ["a short line","and a much longer line which needs wrapping"].into_iter().print_wrapped(20);
+        /// ```
+        fn print_wrapped(self,width: usize);
     }
 
     /// provides a `debug` method on iterators
     pub trait PrintDbg {
         ///  Consume values that implement Debug
         ///  and print them out to stdout with the given terminator.
+        ///  Like `print`, the output is buffered until the end.
         ///
         /// ```
         ///  use easy_shortcuts::traits::PrintDbg;
@@ -127,11 +374,28 @@ pub mod traits {
         fn debug(self,delim: &str);
     }
 
+    /// provides a `print_peek` method on peekable iterators
+    pub trait PrintPeek {
+        /// print the next value to stderr without consuming it,
+        /// or `<end>` if there are no more values.
+        ///
+        /// ```
+        ///  use easy_shortcuts::traits::PrintPeek;
+        ///
+        ///  let mut iter = (1..4).peekable();
+        ///  iter.print_peek();
+        ///  assert_eq!(iter.next(),Some(1));
+        /// ```
+        fn print_peek(&mut self);
+    }
+
     /// Perl-like 'die' quit on error
     pub trait Die<T> {
         /// this is like `expect` but quits with non-zero code
         /// instead of panicking. Defined for Option<T>
-        /// and Result<T,E>
+        /// and Result<T,E>. This includes the `LockResult`
+        /// and `TryLockResult` from `Mutex` and `RwLock`,
+        /// so a poisoned lock quits rather than panics.
         ///
         /// extern crate easy_shortcuts;
         /// use easy_shortcuts::traits::Die;
@@ -150,6 +414,48 @@ pub mod traits {
         /// let = env::home_dir().or_then_die(|_| "no home!".to_string());
         fn or_then_die<C>(self, callback: C) -> T
         where C: Fn(&str)->String;
+
+        /// like `or_die`, but the message is only built by calling `f`
+        /// when actually dying. For `Result<T,E>` the error is appended
+        /// to the message as with `or_die`.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::Die;
+        ///
+        /// let mut called = false;
+        /// let n = "42".parse::<i32>().or_die_with(|| { called = true; "bad number".to_string() });
+        /// assert_eq!(n,42);
+        /// let v = Some(10).or_die_with(|| { called = true; "no value".to_string() });
+        /// assert_eq!(v,10);
+        /// assert!(! called);
+        /// ```
+        fn or_die_with<F: FnOnce()->String>(self, f: F) -> T;
+    }
+
+    /// telling programmer errors apart from user errors
+    pub trait DieUnlessBug<T,E> {
+        /// like `or_die`, except that errors for which `is_bug` is true
+        /// panic instead, so that they get a backtrace.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::DieUnlessBug;
+        /// use std::panic;
+        ///
+        /// // so that quitting can be caught here
+        /// std::env::set_var("EASY_DONT_QUIT_PANIC","1");
+        /// let is_bug = |e: &String| e.starts_with("internal");
+        /// let message = |r: Result<i32,String>| {
+        ///     let err = panic::catch_unwind(|| r.or_die_unless_bug("config",is_bug)).unwrap_err();
+        ///     err.downcast_ref::<String>().unwrap().clone()
+        /// };
+        ///
+        /// assert_eq!(Ok(42).or_die_unless_bug("config",is_bug),42);
+        /// // a user error quits as usual
+        /// assert!(message(Err("no such file".into())).ends_with("error: config no such file"));
+        /// // but a bug panics
+        /// assert_eq!(message(Err("internal state".into())),"config internal state");
+        /// ```
+        fn or_die_unless_bug<F: Fn(&E) -> bool>(self, msg: &str, is_bug: F) -> T;
     }
 
     /// useful extra string operations
@@ -185,441 +491,3688 @@ pub mod traits {
 
         /// does this string only contain whitespace?
         fn is_whitespace(&self) -> bool;
-    }
 
-    /// trims pairs of strings, passes through None
-    pub trait MaybeTrim {
-        /// this operates on pairs of strings that may be empty
-        /// and creates a pair of trimmed strings if possible.
-        ///
-        /// It's designed to work with methods like `split_at_delim`
+        /// collapse any run of whitespace into a single space,
+        /// and trim the ends. A string containing only whitespace
+        /// becomes empty.
         ///
         /// ```
-        /// use easy_shortcuts::traits::*;
+        /// use easy_shortcuts::traits::StringEx;
         ///
-        /// let s = " one = two ".split_at_delim('=').trim();
-        /// assert_eq!(s,Some(("one".to_string(),"two".to_string())));
+        /// let s = "  a   b \t c ".normalize_whitespace();
+        /// assert_eq!(s,"a b c");
+        /// assert_eq!(" \t ".normalize_whitespace(),"");
         /// ```
-        fn trim(self) -> Option<(String,String)>;
-    }
+        fn normalize_whitespace(&self) -> String;
 
+        /// the character at a given position, counting
+        /// characters rather than bytes. `None` if out of range.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::StringEx;
+        ///
+        /// let s = "héllo";
+        /// assert_eq!(s.char_at(1),Some('é'));
+        /// assert_eq!(s.char_at(4),Some('o'));
+        /// assert_eq!(s.char_at(5),None);
+        /// ```
+        fn char_at(&self, idx: usize) -> Option<char>;
 
-    /// file system queries. They are defined
-    /// on `io.Result<fs::Metadata>`.
-    pub trait MetadataLike {
-        /// is this a directory?
+        /// number of characters (not bytes) in the string.
         ///
         /// ```
-        /// use easy_shortcuts::traits::MetadataLike;
-        /// use std::fs;
+        /// use easy_shortcuts::traits::StringEx;
         ///
-        /// let res = fs::metadata(".").is_dir();
-        /// assert!(res);
+        /// assert_eq!("héllo".char_len(),5);
+        /// assert_eq!("héllo".len(),6);
         /// ```
-        fn is_dir(self) -> bool;
+        fn char_len(&self) -> usize;
 
-        /// is this a file?
+        /// the number of terminal columns needed to display the string.
+        /// With the `unicode` feature this accounts for wide characters
+        /// (like CJK and emoji) and zero-width combining marks; otherwise
+        /// it is just the number of characters.
         ///
         /// ```
-        /// use easy_shortcuts::traits::MetadataLike;
-        /// use std::fs;
+        /// use easy_shortcuts::traits::StringEx;
         ///
-        /// let res = fs::metadata("bonzo.dog").is_file();
-        /// assert!(! res);
+        /// assert_eq!("hello".display_width(),5);
         /// ```
-        fn is_file(self) -> bool;
-    }
+        fn display_width(&self) -> usize;
 
-}
+        /// clip the string to fit in `width` columns, ending with `…`
+        /// if anything had to be removed.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::StringEx;
+        ///
+        /// assert_eq!("hello world".truncate_ellipsis(8),"hello w…");
+        /// assert_eq!("hello".truncate_ellipsis(8),"hello");
+        /// ```
+        fn truncate_ellipsis(&self, width: usize) -> String;
 
-use traits::*;
-use std::path;
-use std::fs;
+        /// clip the string to fit in `width` columns like `truncate_ellipsis`,
+        /// but take the characters out of the middle, so that both ends of
+        /// a long path can be seen. Any odd column goes to the start.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::StringEx;
+        ///
+        /// assert_eq!("/home/user/projects/easy/src/lib.rs".truncate_middle(16),"/home/us…/lib.rs");
+        /// assert_eq!("short".truncate_middle(16),"short");
+        /// ```
+        fn truncate_middle(&self, width: usize) -> String;
 
-impl <T> StringEx for T where T: AsRef<str> {
-    fn split_at_delim(&self, delim: char) -> Option<(&str,&str)> {
-        let s = self.as_ref();
-        if let Some(idx) = s.find(delim) {
-            Some((&s[0..idx],&s[idx+1..]))
-        } else {
-            None
-        }
-    }
+        /// pad with spaces on the right to make the string `width`
+        /// columns wide. Longer strings are returned unchanged.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::StringEx;
+        ///
+        /// assert_eq!("ab".pad_right(4),"ab  ");
+        /// assert_eq!("abcde".pad_right(4),"abcde");
+        /// ```
+        fn pad_right(&self, width: usize) -> String;
 
-    fn split_at_delim_right(&self, delim: char) -> Option<(&str,&str)> {
-        let s = self.as_ref();
-        if let Some(idx) = s.rfind(delim) {
-            Some((&s[0..idx],&s[idx+1..]))
-        } else {
-            None
-        }
-    }
+        /// pad with spaces on the left to make the string `width`
+        /// columns wide. Longer strings are returned unchanged.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::StringEx;
+        ///
+        /// assert_eq!("42".pad_left(4),"  42");
+        /// ```
+        fn pad_left(&self, width: usize) -> String;
 
-    fn is_whitespace(&self) -> bool {
-        let s = self.as_ref();
-        s.matches(char::is_whitespace).count() == s.len()
-    }
-}
+        /// the string with `prefix` removed if it starts with it,
+        /// otherwise the string unchanged.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::StringEx;
+        ///
+        /// assert_eq!("./src/lib.rs".without_prefix("./"),"src/lib.rs");
+        /// assert_eq!("src/lib.rs".without_prefix("./"),"src/lib.rs");
+        /// ```
+        fn without_prefix(&self, prefix: &str) -> &str;
 
-impl <'a>MaybeTrim for Option<(&'a str,&'a str)> {
+        /// the string with `suffix` removed if it ends with it,
+        /// otherwise the string unchanged.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::StringEx;
+        ///
+        /// assert_eq!("lib.rs".without_suffix(".rs"),"lib");
+        /// assert_eq!("lib.c".without_suffix(".rs"),"lib.c");
+        /// ```
+        fn without_suffix(&self, suffix: &str) -> &str;
 
-    fn trim(self) -> Option<(String,String)> {
-        match self {
-            Some((s1,s2)) => Some((s1.trim().to_string(),s2.trim().to_string())),
-            None => None
-        }
-    }
+        /// does the trimmed string parse as an integer?
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::StringEx;
+        ///
+        /// assert!(" -42 ".is_integer());
+        /// assert!(! "4.2".is_integer());
+        /// ```
+        fn is_integer(&self) -> bool;
 
-}
+        /// does the trimmed string parse as a floating-point number?
+        /// Integers count, but `inf` and `NaN` do not.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::StringEx;
+        ///
+        /// assert!("-4.2e3".is_float());
+        /// assert!("42".is_float());
+        /// assert!(! "NaN".is_float());
+        /// ```
+        fn is_float(&self) -> bool;
 
+        /// is the trimmed string either an integer or a float?
+        fn is_numeric(&self) -> bool;
 
-impl <T,E> Die<T> for Result<T,E>
-where E: Display {
-    fn or_die(self, msg: &str) -> T {
-        match self {
-            Ok(t) => t,
-            Err(e) => quit(&format!("{} {}", msg,e))
-        }
-    }
+        /// interpret common spellings of true and false, ignoring case
+        /// and surrounding whitespace. `true`, `t`, `yes`, `y`, `on` and `1`
+        /// mean true; `false`, `f`, `no`, `n`, `off` and `0` mean false.
+        /// Anything else gives `None`.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::StringEx;
+        ///
+        /// assert_eq!("Yes".to_bool(),Some(true));
+        /// assert_eq!("off".to_bool(),Some(false));
+        /// assert_eq!("maybe".to_bool(),None);
+        /// ```
+        fn to_bool(&self) -> Option<bool>;
 
-    fn or_then_die<C>(self, callback: C) -> T
-    where C: Fn(&str)->String {
-        match self {
-            Ok(t) => t,
-            Err(e) => quit(&callback(&e.to_string()))
-        }
-    }
+        /// the string with `left` before and `right` after it.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::StringEx;
+        ///
+        /// assert_eq!("x".surround("[","]"),"[x]");
+        /// assert_eq!("x".surround("<!-- "," -->"),"<!-- x -->");
+        /// assert_eq!("x".surround("",""),"x");
+        /// ```
+        fn surround(&self, left: &str, right: &str) -> String;
 
-}
+        /// the string with `with` on both sides, like quotes.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::StringEx;
+        ///
+        /// assert_eq!("hello".wrap("'"),"'hello'");
+        /// assert_eq!("bold".wrap("**"),"**bold**");
+        /// assert_eq!("".wrap("\""),"\"\"");
+        /// ```
+        fn wrap(&self, with: &str) -> String;
 
-impl <T> Die<T> for Option<T>  {
-    fn or_die(self, msg: &str) -> T {
-        match self {
-            Some(t) => t,
-            None => quit(msg)
-        }
+        /// a lowercase identifier suitable for URLs and file names,
+        /// where each run of other characters becomes a single hyphen.
+        /// Letters outside ASCII, like accented letters, are kept as they are.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::StringEx;
+        ///
+        /// assert_eq!("Hello, World!".slugify(),"hello-world");
+        /// assert_eq!("  Café au lait ".slugify(),"café-au-lait");
+        /// assert_eq!("!?!".slugify(),"");
+        /// ```
+        fn slugify(&self) -> String;
+
+        /// a readable label from a `snake_case` or `kebab-case` identifier,
+        /// with each word capitalized. Leading, trailing and repeated
+        /// separators are dropped, and digits stay with their word.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::StringEx;
+        ///
+        /// assert_eq!("my_long_name".humanize(),"My Long Name");
+        /// assert_eq!("my-long-name".humanize(),"My Long Name");
+        /// assert_eq!("__private_v2__".humanize(),"Private V2");
+        /// assert_eq!("2nd_place".humanize(),"2nd Place");
+        /// ```
+        fn humanize(&self) -> String;
+
+        /// split a `camelCase` or `PascalCase` identifier into its words.
+        /// A run of capitals is an acronym, which ends before a capital
+        /// followed by a lowercase letter. Digits belong to the word before
+        /// them, and a capital after a digit starts a new word. Other
+        /// characters like underscores do not split.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::StringEx;
+        ///
+        /// assert_eq!("camelCase".split_camel(),&["camel","Case"]);
+        /// assert_eq!("HTTPServer".split_camel(),&["HTTP","Server"]);
+        /// assert_eq!("base64Encode".split_camel(),&["base64","Encode"]);
+        /// ```
+        fn split_camel(&self) -> Vec<String>;
+
+        /// convert an identifier to `snake_case`. Words are split at
+        /// underscores, hyphens, spaces and camel case boundaries.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::StringEx;
+        ///
+        /// assert_eq!("HelloWorld".to_snake_case(),"hello_world");
+        /// assert_eq!("parseHTTPResponse".to_snake_case(),"parse_http_response");
+        /// ```
+        fn to_snake_case(&self) -> String;
+
+        /// convert an identifier to `camelCase`, splitting words
+        /// like `to_snake_case`.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::StringEx;
+        ///
+        /// assert_eq!("hello_world".to_camel_case(),"helloWorld");
+        /// assert_eq!("max-line width".to_camel_case(),"maxLineWidth");
+        /// ```
+        fn to_camel_case(&self) -> String;
+
+        /// the edit distance to another string: the number of characters
+        /// which must be inserted, deleted or replaced to turn one into the other.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::StringEx;
+        ///
+        /// assert_eq!("kitten".levenshtein("sitting"),3);
+        /// assert_eq!("same".levenshtein("same"),0);
+        /// ```
+        fn levenshtein(&self, other: &str) -> usize;
+
+        /// how alike two strings are, from 1.0 for identical strings
+        /// to 0.0 for completely different ones. This is the edit distance
+        /// as a fraction of the longer length, taken away from one.
+        /// Two empty strings are identical.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::StringEx;
+        ///
+        /// assert_eq!("color".similarity("colour"),1.0 - 1.0/6.0);
+        /// ```
+        fn similarity(&self, other: &str) -> f64;
+
+        /// how many times each character occurs.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::StringEx;
+        ///
+        /// let freqs = "hello".char_frequencies();
+        /// assert_eq!(freqs[&'l'],2);
+        /// assert_eq!(freqs[&'h'],1);
+        /// ```
+        fn char_frequencies(&self) -> HashMap<char,usize>;
+
+        /// how many times each whitespace-separated word occurs.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::StringEx;
+        ///
+        /// let freqs = "the cat and the hat".word_frequencies();
+        /// assert_eq!(freqs["the"],2);
+        /// assert_eq!(freqs.len(),4);
+        /// ```
+        fn word_frequencies(&self) -> HashMap<String,usize>;
+
+        /// the character index of a byte offset, like one from `find`.
+        /// The end of the string is a valid offset; `None` if the offset
+        /// is past the end or not at the start of a character.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::StringEx;
+        ///
+        /// let s = "héllo wörld";
+        /// let byte = s.find('w').unwrap();
+        /// assert_eq!(byte,7);
+        /// assert_eq!(s.byte_to_char(byte),Some(6));
+        /// assert_eq!(s.byte_to_char(2),None);
+        /// assert_eq!(s.byte_to_char(s.len()),Some(11));
+        /// ```
+        fn byte_to_char(&self, byte_idx: usize) -> Option<usize>;
+
+        /// the byte offset of a character index. The end of the string
+        /// is a valid index; `None` if the index is past the end.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::StringEx;
+        ///
+        /// let s = "héllo wörld";
+        /// assert_eq!(s.char_to_byte(6),Some(7));
+        /// assert_eq!(&s[s.char_to_byte(7).unwrap()..],"örld");
+        /// assert_eq!(s.char_to_byte(11),Some(s.len()));
+        /// assert_eq!(s.char_to_byte(12),None);
+        /// ```
+        fn char_to_byte(&self, char_idx: usize) -> Option<usize>;
+
+        /// the byte offsets of all the non-overlapping occurrences of
+        /// `needle`, from left to right. An empty needle matches nowhere.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::StringEx;
+        ///
+        /// assert_eq!("one two one".find_all("one"),&[0,8]);
+        /// assert_eq!("aaaa".find_all("aa"),&[0,2]);
+        /// ```
+        fn find_all(&self, needle: &str) -> Vec<usize>;
+
+        /// replace each `{name}` with `lookup("name")`, leaving it as it
+        /// is if `lookup` gives `None`. Write `{{` for a literal `{` and `}}`
+        /// for a literal `}`, as with `format!`.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::StringEx;
+        ///
+        /// let lookup = |name: &str| match name {
+        ///     "file" => Some("main.rs".to_string()),
+        ///     "size" => Some((10*1024).to_string()),
+        ///     _ => None
+        /// };
+        /// assert_eq!("{file} is {size} bytes".interpolate(lookup),"main.rs is 10240 bytes");
+        /// assert_eq!("{file} {other}".interpolate(lookup),"main.rs {other}");
+        /// assert_eq!("{{file}} {file".interpolate(lookup),"{file} {file");
+        /// ```
+        fn interpolate<F: Fn(&str) -> Option<String>>(&self, lookup: F) -> String;
     }
 
-    fn or_then_die<C>(self, callback: C) -> T
-    where C: Fn(&str)->String  {
-        match self {
-            Some(t) => t,
-            None => quit(&callback("none"))
-        }
+    /// rejoins hard-wrapped text on iterators over lines
+    pub trait UnwrapParagraphs: Sized {
+        /// join the consecutive non-blank lines of each paragraph with
+        /// a space, yielding one string per paragraph. Blank lines are
+        /// passed through as empty strings, so the paragraph breaks remain.
+        /// A line ending in a hyphen is joined to the next without a space.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::*;
+        ///
+        /// let text = "one two\nthree\n\nfour hyph-\nenated\n";
+        /// let res = text.lines().unwrap_paragraphs().to_vec();
+        /// assert_eq!(res,&["one two three","","four hyph-enated"]);
+        /// ```
+        fn unwrap_paragraphs(self) -> ::ParagraphIter<Self>;
     }
 
-}
+    /// limits iterators over strings by their total size
+    pub trait TakeBytes: Sized {
+        /// yield strings until their total length in bytes would
+        /// exceed `max`, and then stop. The total never goes over
+        /// the budget, so a first string longer than `max` means
+        /// nothing is yielded at all.
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::*;
+        ///
+        /// let v = "one two three four".split_whitespace().take_bytes(10).to_vec();
+        /// assert_eq!(v,&["one","two"]);
+        /// ```
+        fn take_bytes(self, max: usize) -> ::TakeBytesIter<Self>;
+    }
 
-use std::iter::FromIterator;
+    /// trims pairs of strings, passes through None
+    pub trait MaybeTrim {
+        /// this operates on pairs of strings that may be empty
+        /// and creates a pair of trimmed strings if possible.
+        ///
+        /// It's designed to work with methods like `split_at_delim`
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::*;
+        ///
+        /// let s = " one = two ".split_at_delim('=').trim();
+        /// assert_eq!(s,Some(("one".to_string(),"two".to_string())));
+        /// ```
+        fn trim(self) -> Option<(String,String)>;
+    }
 
-impl <T,I> ToVec<T> for I
-where T: Sized, I: Iterator<Item=T> {
-    fn to_vec(self) -> Vec<T> {
-        FromIterator::from_iter(self)
+
+    /// file system queries. They are defined
+    /// on `io.Result<fs::Metadata>`.
+    #[allow(clippy::wrong_self_convention)]
+    pub trait MetadataLike {
+        /// is this a directory?
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::MetadataLike;
+        /// use std::fs;
+        ///
+        /// let res = fs::metadata(".").is_dir();
+        /// assert!(res);
+        /// ```
+        fn is_dir(self) -> bool;
+
+        /// is this a file?
+        ///
+        /// ```
+        /// use easy_shortcuts::traits::MetadataLike;
+        /// use std::fs;
+        ///
+        /// let res = fs::metadata("bonzo.dog").is_file();
+        /// assert!(! res);
+        /// ```
+        fn is_file(self) -> bool;
+
+        /// when was this created? `None` if there is an error,
+        /// or the platform does not record creation times.
+        fn created(self) -> Option<SystemTime>;
     }
+
 }
 
-use std::cmp::Eq;
-use std::hash::Hash;
+use traits::*;
+use std::path;
+use std::fs;
 
-impl <K,V,I> ToMap<K,V> for I
-where K: Eq + Hash, V: Sized, I: Iterator<Item=(K,V)>   {
-    fn to_map(self) -> HashMap<K,V> {
-        FromIterator::from_iter(self)
+impl <T> StringEx for T where T: AsRef<str> {
+    fn split_at_delim(&self, delim: char) -> Option<(&str,&str)> {
+        let s = self.as_ref();
+        if let Some(idx) = s.find(delim) {
+            Some((&s[0..idx],&s[idx+1..]))
+        } else {
+            None
+        }
     }
-}
 
-impl <T,I> Join for I
-where T: AsRef<str>, I: Iterator<Item=T> {
-    fn join(self, delim: char) -> String {
+    fn split_at_delim_right(&self, delim: char) -> Option<(&str,&str)> {
+        let s = self.as_ref();
+        if let Some(idx) = s.rfind(delim) {
+            Some((&s[0..idx],&s[idx+1..]))
+        } else {
+            None
+        }
+    }
+
+    fn is_whitespace(&self) -> bool {
+        let s = self.as_ref();
+        s.matches(char::is_whitespace).count() == s.len()
+    }
+
+    fn normalize_whitespace(&self) -> String {
         let mut res = String::new();
-        for v in self {
-            res.push_str(v.as_ref());
-            res.push(delim);
+        for word in self.as_ref().split_whitespace() {
+            if ! res.is_empty() {
+                res.push(' ');
+            }
+            res.push_str(word);
         }
-        res.pop();
         res
     }
 
-    fn prepend(self, prefix: &str) -> String  {
+    fn char_at(&self, idx: usize) -> Option<char> {
+        self.as_ref().chars().nth(idx)
+    }
+
+    fn char_len(&self) -> usize {
+        self.as_ref().chars().count()
+    }
+
+    #[cfg(feature = "unicode")]
+    fn display_width(&self) -> usize {
+        unicode_width::UnicodeWidthStr::width(self.as_ref())
+    }
+
+    #[cfg(not(feature = "unicode"))]
+    fn display_width(&self) -> usize {
+        self.char_len()
+    }
+
+    fn truncate_ellipsis(&self, width: usize) -> String {
+        let s = self.as_ref();
+        if s.display_width() <= width {
+            return s.to_string();
+        }
+        // leave room for the ellipsis
         let mut res = String::new();
-        for v in self {
-            res.push_str(&format!("{}{}",prefix,v.as_ref()));
+        let mut used = 1;
+        for c in s.chars() {
+            used += char_width(c);
+            if used > width {
+                break;
+            }
+            res.push(c);
+        }
+        if width > 0 {
+            res.push('…');
         }
         res
     }
 
-    fn append<F: Fn(&str)->String>(self, map: F)->String {
+    fn truncate_middle(&self, width: usize) -> String {
+        let s = self.as_ref();
+        if s.display_width() <= width {
+            return s.to_string();
+        }
+        if width == 0 {
+            return String::new();
+        }
+        // leave room for the ellipsis
+        let tail_width = (width - 1) / 2;
+        let head_width = width - 1 - tail_width;
+        let take = |chars: &mut dyn Iterator<Item=char>, max: usize| {
+            let mut used = 0;
+            let mut res = Vec::new();
+            for c in chars {
+                used += char_width(c);
+                if used > max {
+                    break;
+                }
+                res.push(c);
+            }
+            res
+        };
+        let mut res: String = take(&mut s.chars(),head_width).into_iter().collect();
+        res.push('…');
+        res.extend(take(&mut s.chars().rev(),tail_width).into_iter().rev());
+        res
+    }
+
+    fn pad_right(&self, width: usize) -> String {
+        let s = self.as_ref();
+        let pad = width.saturating_sub(s.display_width());
+        format!("{}{}",s," ".repeat(pad))
+    }
+
+    fn pad_left(&self, width: usize) -> String {
+        let s = self.as_ref();
+        let pad = width.saturating_sub(s.display_width());
+        format!("{}{}"," ".repeat(pad),s)
+    }
+
+    fn without_prefix(&self, prefix: &str) -> &str {
+        let s = self.as_ref();
+        s.strip_prefix(prefix).unwrap_or(s)
+    }
+
+    fn without_suffix(&self, suffix: &str) -> &str {
+        let s = self.as_ref();
+        s.strip_suffix(suffix).unwrap_or(s)
+    }
+
+    fn is_integer(&self) -> bool {
+        self.as_ref().trim().parse::<i64>().is_ok()
+    }
+
+    fn is_float(&self) -> bool {
+        let s = self.as_ref().trim();
+        s.parse::<f64>().is_ok() && s.contains(|c: char| c.is_ascii_digit())
+    }
+
+    fn is_numeric(&self) -> bool {
+        self.is_integer() || self.is_float()
+    }
+
+    fn to_bool(&self) -> Option<bool> {
+        match self.as_ref().trim().to_lowercase().as_str() {
+            "true" | "t" | "yes" | "y" | "on" | "1" => Some(true),
+            "false" | "f" | "no" | "n" | "off" | "0" => Some(false),
+            _ => None
+        }
+    }
+
+    fn surround(&self, left: &str, right: &str) -> String {
+        format!("{}{}{}",left,self.as_ref(),right)
+    }
+
+    fn wrap(&self, with: &str) -> String {
+        self.surround(with,with)
+    }
+
+    fn slugify(&self) -> String {
         let mut res = String::new();
-        for s in self {
-            res.push_str(&map(s.as_ref()));
+        let mut gap = false;
+        for c in self.as_ref().chars() {
+            if c.is_alphanumeric() {
+                if gap && ! res.is_empty() {
+                    res.push('-');
+                }
+                gap = false;
+                res.extend(c.to_lowercase());
+            } else {
+                gap = true;
+            }
         }
         res
     }
-}
 
+    fn humanize(&self) -> String {
+        self.as_ref().split(['_','-'])
+            .filter(|w| ! w.is_empty())
+            .map(|w| {
+                let mut chars = w.chars();
+                let first = chars.next().unwrap();
+                first.to_uppercase().chain(chars).collect::<String>()
+            })
+            .join(' ')
+    }
 
-impl <T,I> Print for I
-where T: Display, I: Iterator<Item=T> {
-    fn print(self,delim: &str) {
-        for v in self {
-            print!("{}{}",v,delim);
+    fn split_camel(&self) -> Vec<String> {
+        let chars = self.as_ref().chars().to_vec();
+        let mut res = Vec::new();
+        let mut word = String::new();
+        for (i,&c) in chars.iter().enumerate() {
+            if i > 0 && c.is_uppercase() {
+                let prev = chars[i-1];
+                let next_lower = chars.get(i+1).is_some_and(|n| n.is_lowercase());
+                if prev.is_lowercase() || prev.is_numeric() || (prev.is_uppercase() && next_lower) {
+                    res.push(word.clone());
+                    word.clear();
+                }
+            }
+            word.push(c);
         }
+        if ! word.is_empty() {
+            res.push(word);
+        }
+        res
+    }
+
+    fn to_snake_case(&self) -> String {
+        identifier_words(self.as_ref()).iter().map(|w| w.to_lowercase()).join('_')
+    }
+
+    fn to_camel_case(&self) -> String {
+        identifier_words(self.as_ref()).iter().enumerate().map(|(i,w)| {
+            let w = w.to_lowercase();
+            if i == 0 {
+                return w;
+            }
+            let mut chars = w.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => w
+            }
+        }).concat_strings()
+    }
+
+    fn levenshtein(&self, other: &str) -> usize {
+        let b = other.chars().to_vec();
+        // distances from the start of `self` so far to each prefix of `other`
+        let mut row = (0..=b.len()).to_vec();
+        for (i,ca) in self.as_ref().chars().enumerate() {
+            let mut diag = row[0];
+            row[0] = i + 1;
+            for (j,cb) in b.iter().enumerate() {
+                let replace = diag + if ca == *cb {0} else {1};
+                diag = row[j+1];
+                row[j+1] = replace.min(row[j] + 1).min(diag + 1);
+            }
+        }
+        row[b.len()]
+    }
+
+    fn similarity(&self, other: &str) -> f64 {
+        let max_len = self.char_len().max(other.chars().count());
+        if max_len == 0 {
+            return 1.0;
+        }
+        1.0 - self.levenshtein(other) as f64 / max_len as f64
+    }
+
+    fn byte_to_char(&self, byte_idx: usize) -> Option<usize> {
+        let s = self.as_ref();
+        if s.is_char_boundary(byte_idx) {
+            Some(s[..byte_idx].chars().count())
+        } else {
+            None
+        }
+    }
+
+    fn char_to_byte(&self, char_idx: usize) -> Option<usize> {
+        let s = self.as_ref();
+        s.char_indices().map(|(i,_)| i).chain(Some(s.len())).nth(char_idx)
+    }
+
+    fn find_all(&self, needle: &str) -> Vec<usize> {
+        if needle.is_empty() {
+            return Vec::new();
+        }
+        self.as_ref().match_indices(needle).map(|(i,_)| i).collect()
+    }
+
+    fn interpolate<F: Fn(&str) -> Option<String>>(&self, lookup: F) -> String {
+        let mut res = String::new();
+        let mut rest = self.as_ref();
+        while let Some(start) = rest.find(['{','}']) {
+            res.push_str(&rest[..start]);
+            let brace = if rest[start..].starts_with('{') {'{'} else {'}'};
+            let after = &rest[start+1..];
+            if let Some(after) = after.strip_prefix(brace) {
+                res.push(brace);
+                rest = after;
+            } else if brace == '}' {
+                // a lone closing brace is left alone
+                res.push('}');
+                rest = after;
+            } else if let Some(end) = after.find('}') {
+                match lookup(&after[..end]) {
+                    Some(value) => res.push_str(&value),
+                    None => res.push_str(&rest[start..start+end+2])
+                }
+                rest = &after[end+1..];
+            } else {
+                // no closing brace, so not a placeholder
+                res.push('{');
+                rest = after;
+            }
+        }
+        res.push_str(rest);
+        res
+    }
+
+    fn char_frequencies(&self) -> HashMap<char,usize> {
+        let mut res = HashMap::new();
+        for c in self.as_ref().chars() {
+            *res.entry(c).or_insert(0) += 1;
+        }
+        res
+    }
+
+    fn word_frequencies(&self) -> HashMap<String,usize> {
+        let mut res = HashMap::new();
+        for w in self.as_ref().split_whitespace() {
+            *res.entry(w.to_string()).or_insert(0) += 1;
+        }
+        res
+    }
+}
+
+// the words of an identifier in any of the usual conventions
+fn identifier_words(s: &str) -> Vec<String> {
+    s.split(['_','-',' '])
+        .flat_map(|part| part.split_camel())
+        .collect()
+}
+
+#[cfg(feature = "unicode")]
+fn char_width(c: char) -> usize {
+    unicode_width::UnicodeWidthChar::width(c).unwrap_or(0)
+}
+
+#[cfg(not(feature = "unicode"))]
+fn char_width(_c: char) -> usize {
+    1
+}
+
+impl <'a>MaybeTrim for Option<(&'a str,&'a str)> {
+
+    fn trim(self) -> Option<(String,String)> {
+        self.map(|(s1,s2)| (s1.trim().to_string(),s2.trim().to_string()))
+    }
+
+}
+
+
+impl <T,E> Die<T> for Result<T,E>
+where E: Display {
+    fn or_die(self, msg: &str) -> T {
+        match self {
+            Ok(t) => t,
+            Err(e) => quit(&format!("{} {}", msg,e))
+        }
+    }
+
+    fn or_then_die<C>(self, callback: C) -> T
+    where C: Fn(&str)->String {
+        match self {
+            Ok(t) => t,
+            Err(e) => quit(&callback(&e.to_string()))
+        }
+    }
+
+    fn or_die_with<F: FnOnce()->String>(self, f: F) -> T {
+        match self {
+            Ok(t) => t,
+            Err(e) => quit(&format!("{} {}",f(),e))
+        }
+    }
+
+}
+
+impl <T,E> DieUnlessBug<T,E> for Result<T,E>
+where E: Display {
+    fn or_die_unless_bug<F: Fn(&E) -> bool>(self, msg: &str, is_bug: F) -> T {
+        match self {
+            Ok(t) => t,
+            Err(ref e) if is_bug(e) => panic!("{} {}",msg,e),
+            Err(e) => quit(&format!("{} {}",msg,e))
+        }
+    }
+}
+
+impl <T> Die<T> for Option<T>  {
+    fn or_die(self, msg: &str) -> T {
+        match self {
+            Some(t) => t,
+            None => quit(msg)
+        }
+    }
+
+    fn or_then_die<C>(self, callback: C) -> T
+    where C: Fn(&str)->String  {
+        match self {
+            Some(t) => t,
+            None => quit(&callback("none"))
+        }
+    }
+
+    fn or_die_with<F: FnOnce()->String>(self, f: F) -> T {
+        match self {
+            Some(t) => t,
+            None => quit(&f())
+        }
+    }
+
+}
+
+use std::iter::FromIterator;
+
+impl <T,I> ToVec<T> for I
+where T: Sized, I: Iterator<Item=T> {
+    fn to_vec(self) -> Vec<T> {
+        FromIterator::from_iter(self)
+    }
+}
+
+use std::cmp::Eq;
+use std::hash::Hash;
+
+impl <K,V,I> ToMap<K,V> for I
+where K: Eq + Hash, V: Sized, I: Iterator<Item=(K,V)>   {
+    fn to_map(self) -> HashMap<K,V> {
+        FromIterator::from_iter(self)
+    }
+
+    fn to_map_merge<F: Fn(V,V)->V>(self, merge: F) -> HashMap<K,V> {
+        let mut res = HashMap::new();
+        for (k,v) in self {
+            let v = match res.remove(&k) {
+                Some(old) => merge(old,v),
+                None => v
+            };
+            res.insert(k,v);
+        }
+        res
+    }
+}
+
+impl <T,I> CountBy<T> for I
+where I: Iterator<Item=T> {
+    fn count_by_sorted<K: Eq + Hash + Ord, F: Fn(&T) -> K>(self, key: F) -> Vec<(K,usize)> {
+        let mut counts = HashMap::new();
+        for v in self {
+            *counts.entry(key(&v)).or_insert(0) += 1;
+        }
+        let mut res: Vec<_> = counts.into_iter().collect();
+        res.sort_by(|a,b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        res
+    }
+}
+
+impl <T,I> Join for I
+where T: AsRef<str>, I: Iterator<Item=T> {
+    fn join(self, delim: char) -> String {
+        join_with(self,delim.encode_utf8(&mut [0; 4]))
+    }
+
+    fn join_str(self, delim: &str) -> String {
+        join_with(self,delim)
+    }
+
+    fn concat_strings(self) -> String {
+        join_with(self,"")
+    }
+
+    fn join_to(self, delim: char, out: &mut String) {
+        join_into(self,delim.encode_utf8(&mut [0; 4]),out)
+    }
+
+    fn join_non_empty(self, delim: char) -> String {
+        self.filter(|v| ! v.as_ref().is_empty()).join(delim)
+    }
+
+    fn join_non_blank(self, delim: char) -> String {
+        self.filter(|v| ! v.is_whitespace()).join(delim)
+    }
+
+    fn quote_join(self, delim: char) -> String {
+        self.map(|v| format!("'{}'",v.as_ref().replace('\'',r"'\''"))).join(delim)
+    }
+
+    fn csv_line(self) -> String {
+        self.map(|v| {
+            let v = v.as_ref();
+            if v.contains([',','"','\r','\n']) {
+                format!("\"{}\"",v.replace('"',"\"\""))
+            } else {
+                v.to_string()
+            }
+        }).join(',')
+    }
+
+    fn intersperse(self, sep: T) -> Intersperse<Self>
+    where T: Clone {
+        Intersperse{iter: self.peekable(), sep, sep_next: false}
+    }
+
+    fn prepend(self, prefix: &str) -> String  {
+        let mut res = String::new();
+        for v in self {
+            res.push_str(&format!("{}{}",prefix,v.as_ref()));
+        }
+        res
+    }
+
+    fn wrap_each(self, prefix: &str, suffix: &str) -> String {
+        let mut res = String::new();
+        for v in self {
+            res.push_str(prefix);
+            res.push_str(v.as_ref());
+            res.push_str(suffix);
+        }
+        res
+    }
+
+    fn append<F: Fn(&str)->String>(self, map: F)->String {
+        let mut res = String::new();
+        for s in self {
+            res.push_str(&map(s.as_ref()));
+        }
+        res
+    }
+
+    fn append_indexed<F: Fn(usize,&str)->String>(self, map: F)->String {
+        let mut res = String::new();
+        for (i,s) in self.enumerate() {
+            res.push_str(&map(i,s.as_ref()));
+        }
+        res
+    }
+
+    fn join_fold<F: FnMut(&mut String,&str,bool)>(self, mut f: F) -> String {
+        let mut res = String::new();
+        for (i,s) in self.enumerate() {
+            f(&mut res,s.as_ref(),i == 0);
+        }
+        res
+    }
+}
+
+
+const PRINT_BUFFER_SIZE: usize = 8 * 1024;
+
+thread_local! {
+    // output of `print` and `debug` not yet written to stdout
+    static PRINT_BUFFER: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+}
+
+// buffers stdout for `print` and `debug`. The buffer is kept where
+// `quit_text` can flush it, since a value may quit before the end.
+struct BufferedStdout;
+
+impl Write for BufferedStdout {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let full = PRINT_BUFFER.with(|b| {
+            let mut b = b.borrow_mut();
+            b.extend_from_slice(buf);
+            b.len() >= PRINT_BUFFER_SIZE
+        });
+        if full {
+            flush_print_buffer()?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        flush_print_buffer()
+    }
+}
+
+impl Drop for BufferedStdout {
+    fn drop(&mut self) {
+        // only does anything when unwinding from a panic
+        let _ = flush_print_buffer();
+    }
+}
+
+fn flush_print_buffer() -> io::Result<()> {
+    let mut out = io::stdout().lock();
+    PRINT_BUFFER.with(|b| match b.try_borrow_mut() {
+        Ok(mut b) => {
+            let res = out.write_all(&b);
+            b.clear();
+            res
+        },
+        Err(_) => Ok(())
+    })?;
+    out.flush()
+}
+
+impl <T,I> Print for I
+where T: Display, I: Iterator<Item=T> {
+    fn print(self,delim: &str) {
+        let _lock = io::stdout().lock();
+        let mut out = BufferedStdout;
+        self.print_to(&mut out,delim);
+        out.flush().or_die("write");
+    }
+
+    fn eprint(self,delim: &str) {
+        self.print_to(&mut io::stderr(),delim);
+    }
+
+    fn print_to<W: Write>(self, w: &mut W, delim: &str) {
+        for v in self {
+            write!(w,"{}{}",v,delim).or_die("write");
+        }
+    }
+
+    fn print_count(self,delim: &str) -> usize {
+        let mut n = 0;
+        self.inspect(|_| n += 1).print(delim);
+        n
+    }
+
+    fn print_vec(self,delim: &str) -> Vec<T> {
+        let mut res = Vec::new();
+        for v in self {
+            print!("{}{}",v,delim);
+            res.push(v);
+        }
+        res
+    }
+
+    fn print_wrapped(self,width: usize) {
+        for v in self {
+            let s = v.to_string();
+            if s.display_width() > width {
+                print!("{}",reflow(&s,width));
+            } else {
+                println!("{}",s);
+            }
+        }
+    }
+
+    fn print_columns(self,term_width: usize) {
+        let items = self.map(|v| v.to_string()).to_vec();
+        print!("{}",format_columns(&items,term_width));
+    }
+}
+
+fn join_with<T,I>(iter: I, delim: &str) -> String
+where T: AsRef<str>, I: Iterator<Item=T> {
+    let mut res = String::new();
+    join_into(iter,delim,&mut res);
+    res
+}
+
+// the most `join_into` will reserve beyond the first value
+const MAX_JOIN_RESERVE: usize = 1 << 20;
+
+// reserve room for the result up front, guessing that the
+// values are about as long as the first one. The guess is capped,
+// since one long value followed by many short ones would otherwise
+// ask for far too much; past that `push_str` grows the string.
+fn join_into<T,I>(mut iter: I, delim: &str, res: &mut String)
+where T: AsRef<str>, I: Iterator<Item=T> {
+    let first = match iter.next() {
+        Some(v) => v,
+        None => return
+    };
+    let first = first.as_ref();
+    let (lower,_) = iter.size_hint();
+    let rest = lower.saturating_mul(first.len().saturating_add(delim.len()));
+    res.reserve(first.len().saturating_add(rest.min(MAX_JOIN_RESERVE)));
+    res.push_str(first);
+    for v in iter {
+        res.push_str(delim);
+        res.push_str(v.as_ref());
+    }
+}
+
+// the grid for `print_columns`
+fn format_columns(items: &[String], term_width: usize) -> String {
+    const GAP: usize = 2;
+    let widths = items.iter().map(|s| s.display_width()).to_vec();
+    // try the most columns first; one column always works
+    let mut layout = (items.len(),vec![0]);
+    for cols in (2..=items.len()).rev() {
+        let rows = (items.len() + cols - 1) / cols;
+        let col_widths = widths.chunks(rows).map(|c| *c.iter().max().unwrap()).to_vec();
+        let total = col_widths.iter().sum::<usize>() + GAP*(col_widths.len()-1);
+        if total <= term_width {
+            layout = (rows,col_widths);
+            break;
+        }
+    }
+    let (rows,col_widths) = layout;
+    let mut res = String::new();
+    for row in 0..rows {
+        let mut line = String::new();
+        for (col,width) in col_widths.iter().enumerate() {
+            if let Some(item) = items.get(col*rows + row) {
+                line += &item.pad_right(width + GAP);
+            }
+        }
+        res += line.trim_end();
+        res.push('\n');
+    }
+    res
+}
+
+impl <T,I> PrintDbg for I
+where T: Debug, I: Iterator<Item=T> {
+    fn debug(self,delim: &str) {
+        let _lock = io::stdout().lock();
+        let mut out = BufferedStdout;
+        for v in self {
+            write!(out,"{:?}{}",v,delim).or_die("write");
+        }
+        out.flush().or_die("write");
+    }
+}
+
+impl <T,I> PrintPeek for std::iter::Peekable<I>
+where T: Display, I: Iterator<Item=T> {
+    fn print_peek(&mut self) {
+        match self.peek() {
+            Some(v) => eprintln!("{}",v),
+            None => eprintln!("<end>")
+        }
+    }
+}
+
+impl MetadataLike for io::Result<fs::Metadata> {
+    fn is_dir(self) -> bool {
+        match self {
+            Ok(meta) => meta.is_dir(),
+            Err(_) => false
+        }
+    }
+
+    fn is_file(self) -> bool {
+        match self {
+            Ok(meta) => meta.is_file(),
+            Err(_) => false
+        }
+    }
+
+    fn created(self) -> Option<SystemTime> {
+        self.ok().and_then(|meta| meta.created().ok())
+    }
+}
+
+
+/// quit this program, printing a message and returning a non-zero exit code.
+/// Standard output and error are flushed first, so no buffered output is lost.
+pub fn quit(msg: &str) -> !{
+    quit_with_code(1,msg)
+}
+
+/// quit this program like `quit`, but with the given exit code.
+/// Useful when the exit code distinguishes kinds of failure,
+/// like grep's 1 for 'no match' and 2 for 'error'.
+pub fn quit_with_code(code: i32, msg: &str) -> ! {
+    let text = format!("{} error: {}",argn_or(0,""),msg);
+    quit_text(code,&text)
+}
+
+type QuitHandler = Arc<dyn Fn(&str) + Send + Sync>;
+
+fn quit_handler() -> &'static Mutex<Option<QuitHandler>> {
+    static HANDLER: OnceLock<Mutex<Option<QuitHandler>>> = OnceLock::new();
+    HANDLER.get_or_init(|| Mutex::new(None))
+}
+
+/// have `quit` and friends call `f` with the full message just
+/// before exiting, for instance to flush a log. Replaces any
+/// handler set before. If the handler itself quits, it is not
+/// called again, and the program quits with the handler's message.
+///
+/// ```
+/// extern crate easy_shortcuts as es;
+///
+/// es::set_quit_handler(Box::new(|msg| eprintln!("cleaning up after: {}",msg)));
+/// ```
+pub fn set_quit_handler(f: Box<dyn Fn(&str) + Send + Sync>) {
+    *quit_handler().lock().unwrap_or_else(|e| e.into_inner()) = Some(Arc::from(f));
+}
+
+// call any quit handler, and then exit (or panic)
+fn quit_text(code: i32, text: &str) -> ! {
+    thread_local! {
+        static IN_HANDLER: Cell<bool> = const { Cell::new(false) };
+    }
+    // anything a quitting `print` had buffered goes out first
+    let _ = flush_print_buffer();
+    // not holding the lock while it runs, so the handler may quit
+    let handler = quit_handler().lock().unwrap_or_else(|e| e.into_inner()).clone();
+    if let Some(f) = handler {
+        if ! IN_HANDLER.with(|h| h.replace(true)) {
+            f(text);
+            IN_HANDLER.with(|h| h.set(false));
+        }
+    }
+    if std::env::var("EASY_DONT_QUIT_PANIC").is_ok() {
+        panic!("{}", text);
+    } else {
+        exit_with(code,text);
+    }
+}
+
+/// print a warning to stderr, prefixed with the program name
+/// like the messages of `quit`, and carry on.
+pub fn warn(msg: &str) {
+    let _ = flush_print_buffer();
+    let _ = writeln!(&mut io::stderr(),"{} warning: {}",argn_or(0,""),msg);
+}
+
+// write the message to stderr, flush both streams, and exit
+fn exit_with(code: i32, msg: &str) -> ! {
+    let _ = io::stdout().flush();
+    let _ = writeln!(&mut io::stderr(),"{}",msg);
+    let _ = io::stderr().flush();
+    std::process::exit(code);
+}
+
+/// quit this program after a usage error, printing the message
+/// as is and returning the conventional exit code 2.
+pub fn usage(msg: &str) -> ! {
+    quit_text(2,msg)
+}
+
+/// a form of `quit` which works with the standard `Error` type.
+/// The message is the error's `Display` form.
+pub fn quit_err(e: &dyn std::error::Error) -> ! {
+    quit(&e.to_string());
+}
+
+/// a simple error type carrying a message, so that functions
+/// can return `Result<T,EasyError>` and use `?` on i/o results
+/// and string errors alike.
+///
+/// ```
+/// extern crate easy_shortcuts as es;
+/// use es::EasyError;
+///
+/// fn first_line(file: &str) -> Result<String,EasyError> {
+///     let text = std::fs::read_to_string(file)?;
+///     match text.lines().next() {
+///         Some(line) => Ok(line.to_string()),
+///         None => Err(EasyError::new(&format!("{} is empty",file)))
+///     }
+/// }
+///
+/// assert_eq!(first_line("Cargo.toml").unwrap(),"[package]");
+/// assert!(first_line("no-such-file").is_err());
+/// ```
+#[derive(Debug,Clone,PartialEq)]
+pub struct EasyError {
+    msg: String
+}
+
+impl EasyError {
+    /// a new error with the given message.
+    pub fn new(msg: &str) -> EasyError {
+        EasyError{msg: msg.to_string()}
+    }
+}
+
+impl Display for EasyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f,"{}",self.msg)
+    }
+}
+
+impl std::error::Error for EasyError {}
+
+impl From<io::Error> for EasyError {
+    fn from(e: io::Error) -> EasyError {
+        EasyError{msg: e.to_string()}
+    }
+}
+
+impl From<String> for EasyError {
+    fn from(msg: String) -> EasyError {
+        EasyError{msg}
+    }
+}
+
+impl<'a> From<&'a str> for EasyError {
+    fn from(msg: &'a str) -> EasyError {
+        EasyError::new(msg)
+    }
+}
+
+/// quit with the given exit code, printing the error
+/// followed by each of its underlying causes.
+pub fn quit_err_code(code: i32, e: &dyn std::error::Error) -> ! {
+    let mut text = format!("{} error: {}",argn_or(0,""),e);
+    let mut source = e.source();
+    while let Some(cause) = source {
+        text += &format!(": {}",cause);
+        source = cause.source();
+    }
+    quit_text(code,&text)
+}
+
+/// quit works like try, except it quits instead of returning.
+/// The error message is output using `quit_err`. With a second
+/// argument, it quits with that exit code using `quit_err_code`.
+///
+/// ```
+/// #[macro_use]
+/// extern crate easy_shortcuts;
+/// use std::fs;
+///
+/// fn main() {
+///     let md = quit!(fs::metadata("."));
+/// }
+/// ```
+#[macro_export]
+macro_rules! quit {
+    ($e:expr) => (match $e { Ok(val) => val, Err(err) => $crate::quit_err(&err) });
+    ($e:expr, $code:expr) => (match $e { Ok(val) => val, Err(err) => $crate::quit_err_code($code,&err) });
+}
+
+// these come after `quit!` so that they can use it
+pub mod json;
+pub use json::{JsonValue,parse_json};
+pub mod ini;
+pub use ini::{read_ini,write_ini};
+pub mod cargo;
+pub use cargo::{toml_get,crate_name_from_manifest,cargo_target_dir};
+pub mod archive;
+pub use archive::{archive,unarchive};
+pub mod flags;
+pub use flags::{Flags,Args};
+pub mod walk;
+pub use walk::{all_paths,AllPaths};
+pub mod progress;
+pub use progress::{Bar,render_bar};
+#[cfg(feature = "threads")]
+pub mod parallel;
+#[cfg(feature = "threads")]
+pub use parallel::ParallelPaths;
+
+/// get the nth command-line argument or return the default.
+///
+/// ```
+/// extern crate easy_shortcuts as es;
+///
+/// let arg = es::argn_or(1,"default");
+/// assert_eq!(arg,"default");
+/// ```
+pub fn argn_or(idx: usize, def: &str) -> String {
+    std::env::args().nth(idx).unwrap_or(def.to_string())
+}
+
+/// get the nth argument or quit with a message.
+pub fn argn_err(idx: usize, msg: &str) -> String {
+    match std::env::args().nth(idx) {
+        Some(s) => s,
+        None => quit(&format!("no argument {}: {}",idx,msg))
+    }
+}
+
+/// the command-line arguments, with response files expanded.
+/// Any argument like `@args.txt` is replaced by the whitespace-separated
+/// words in that file; will quit if the file cannot be read.
+pub fn expanded_args() -> Vec<String> {
+    expand_args(std::env::args())
+}
+
+fn expand_args<I: Iterator<Item=String>>(args: I) -> Vec<String> {
+    let mut res = Vec::new();
+    for arg in args {
+        if arg.starts_with('@') && arg.len() > 1 {
+            res.extend(read_to_string(&arg[1..]).split_whitespace().map(|s| s.to_string()));
+        } else {
+            res.push(arg);
+        }
+    }
+    res
+}
+
+use std::path::Path;
+
+/// open a file for reading, quitting if there's any error.
+pub fn open<P: AsRef<Path>>(file: P) -> File {
+    match  File::open(&file) {
+        Ok(f) => f,
+        Err(e) => quit(&format!("open {:?} {}",file.as_ref(),e))
+    }
+}
+
+/// call `f` up to `tries` times, sleeping for `delay` between attempts,
+/// until it succeeds. Only errors for which `can_retry` is true are retried;
+/// others are returned at once, as is the last error.
+///
+/// ```
+/// extern crate easy_shortcuts as es;
+/// use std::time::Duration;
+///
+/// let mut calls = 0;
+/// let res = es::retry(5,Duration::from_millis(1),|| {
+///     calls += 1;
+///     if calls < 3 { Err("busy") } else { Ok(calls) }
+/// }, |e| *e == "busy");
+/// assert_eq!(res,Ok(3));
+/// ```
+pub fn retry<T,E,F,C>(tries: u32, delay: Duration, mut f: F, mut can_retry: C) -> Result<T,E>
+where F: FnMut() -> Result<T,E>, C: FnMut(&E) -> bool {
+    let mut attempt = 1;
+    loop {
+        match f() {
+            Err(ref e) if attempt < tries && can_retry(e) => {
+                attempt += 1;
+                std::thread::sleep(delay);
+            },
+            res => return res
+        }
+    }
+}
+
+// permission errors, and on Windows sharing and lock violations,
+// may go away when another process lets go of the file
+fn is_transient(e: &io::Error) -> bool {
+    e.kind() == io::ErrorKind::PermissionDenied
+        || (cfg!(windows) && (e.raw_os_error() == Some(32) || e.raw_os_error() == Some(33)))
+}
+
+/// open a file for reading like `open`, but retry if another process
+/// has it locked, trying `tries` times with `delay` between attempts.
+/// Quits if the file still cannot be opened.
+pub fn open_retry<P: AsRef<Path>>(file: P, tries: u32, delay: Duration) -> File {
+    match retry(tries,delay,|| File::open(&file),is_transient) {
+        Ok(f) => f,
+        Err(e) => quit(&format!("open {:?} {}",file.as_ref(),e))
+    }
+}
+
+/// open a buffered file for reading, quitting if there's any error.
+pub fn open_buffered<P: AsRef<Path>>(file: P) -> io::BufReader<File> {
+    io::BufReader::new(open(file))
+}
+
+/// create a file for writing, quitting if not possible.
+pub fn create<P: AsRef<Path>>(file: P) -> File {
+    match File::create(&file) {
+        Ok(f) => f,
+        Err(e) => quit(&format!("create {:?} {}",file.as_ref(),e))
+    }
+}
+
+/// open a file for reading, or standard input if the path is `-`.
+/// Quits if the file cannot be opened.
+pub fn open_or_stdin(path: &str) -> Box<dyn io::Read> {
+    if path == "-" {
+        Box::new(io::stdin())
+    } else {
+        Box::new(open(path))
+    }
+}
+
+/// create a file for writing, or use standard output if the path is `-`.
+/// Quits if the file cannot be created.
+pub fn create_or_stdout(path: &str) -> Box<dyn io::Write> {
+    if path == "-" {
+        Box::new(io::stdout())
+    } else {
+        Box::new(create(path))
+    }
+}
+
+/// read the contents of a file as a string, quitting otherwise
+pub fn read_to_string<P: AsRef<Path>>(file: P) -> String {
+    let mut f = open(file);
+    let mut s = String::new();
+    quit!(f.read_to_string(&mut s));
+    s
+}
+
+/// read the contents of a file as a string like `read_to_string`,
+/// but keep the contents for later calls. The file is read again
+/// if its modification time or size changes.
+pub fn read_cached<P: AsRef<Path>>(file: P) -> String {
+    // contents, with the modification time and size when read
+    type Cache = HashMap<path::PathBuf,(SystemTime,u64,String)>;
+    static CACHE: OnceLock<Mutex<Cache>> = OnceLock::new();
+
+    let path = match fs::canonicalize(&file) {
+        Ok(p) => p,
+        Err(e) => quit(&format!("open {:?} {}",file.as_ref(),e))
+    };
+    let meta = quit!(fs::metadata(&path));
+    let stamp = (quit!(meta.modified()),meta.len());
+    // not holding the lock while reading, since that may quit
+    let cache = || CACHE.get_or_init(|| Mutex::new(HashMap::new())).lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(&(modified,len,ref text)) = cache().get(&path) {
+        if (modified,len) == stamp {
+            return text.clone();
+        }
+    }
+    let text = read_to_string(&path);
+    cache().insert(path,(stamp.0,stamp.1,text.clone()));
+    text
+}
+
+/// write a String to a new file, or quit
+pub fn write_all<P: AsRef<Path>>(file: P, buff: &str) {
+    quit!(create(file).write_all(buff.as_bytes()));
+}
+
+/// write a String to a new file like `write_all`, then read it back
+/// and quit if the contents are not what was written.
+/// The file is synced to disk before it is read.
+pub fn write_verified<P: AsRef<Path>>(file: P, buff: &str) {
+    let mut f = create(&file);
+    quit!(f.write_all(buff.as_bytes()));
+    quit!(f.sync_all());
+    drop(f);
+    if quit!(fs::read(&file)) != buff.as_bytes() {
+        quit(&format!("write {:?}: contents read back differ",file.as_ref()));
+    }
+}
+
+/// copy each of the input files into a new output file, quitting on error.
+/// If `header` is given, it is written as a line before each file,
+/// with `{}` replaced by the file's name.
+///
+/// ```
+/// extern crate easy_shortcuts as es;
+/// # let dir = std::env::temp_dir();
+/// # let (one,two) = (dir.join("easy-shortcuts-doc-one.txt"),dir.join("easy-shortcuts-doc-two.txt"));
+/// # es::write_all(&one,"first\n");
+/// # es::write_all(&two,"second\n");
+/// let out = dir.join("easy-shortcuts-doc-both.txt");
+///
+/// es::concat_files(&[&one,&two],&out,None);
+/// assert_eq!(es::read_to_string(&out),"first\nsecond\n");
+/// ```
+pub fn concat_files<P: AsRef<Path>, Q: AsRef<Path>>(inputs: &[P], output: Q, header: Option<&str>) {
+    let mut out = io::BufWriter::new(create(output));
+    for file in inputs {
+        if let Some(header) = header {
+            let name = file.as_ref().display().to_string();
+            quit!(writeln!(out,"{}",header.replace("{}",&name)));
+        }
+        quit!(io::copy(&mut open(file),&mut out));
+    }
+    quit!(out.flush());
+}
+
+/// a temporary directory which is removed, with everything
+/// in it, when this value is dropped. Created by `temp_dir_guard`.
+#[derive(Debug)]
+pub struct TempDir {
+    path: path::PathBuf,
+    keep: bool
+}
+
+/// create a new, empty directory in the system temporary directory,
+/// with a name starting with `prefix`. It goes away when the guard is
+/// dropped. Quits if the directory cannot be created.
+///
+/// ```
+/// extern crate easy_shortcuts as es;
+///
+/// let path = {
+///     let tmp = es::temp_dir_guard("scratch");
+///     es::write_all(tmp.path().join("hello.txt"),"hello");
+///     tmp.path().to_path_buf()
+/// };
+/// assert!(! path.exists());
+/// ```
+pub fn temp_dir_guard(prefix: &str) -> TempDir {
+    use std::sync::atomic::{AtomicUsize,Ordering};
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+    let base = std::env::temp_dir();
+    loop {
+        let stamp = SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.subsec_nanos()).unwrap_or(0);
+        let name = format!("{}-{}-{}-{}",prefix,std::process::id(),COUNT.fetch_add(1,Ordering::SeqCst),stamp);
+        let path = base.join(name);
+        match fs::create_dir(&path) {
+            Ok(_) => return TempDir{path, keep: false},
+            Err(ref e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => quit(&format!("create {:?} {}",path,e))
+        }
+    }
+}
+
+impl TempDir {
+    /// where the directory is.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// don't remove the directory after all, returning its path.
+    pub fn keep(mut self) -> path::PathBuf {
+        self.keep = true;
+        self.path.clone()
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        if ! self.keep {
+            let _ = fs::remove_dir_all(&self.path);
+        }
+    }
+}
+
+/// copy the lines of `input` to `output`, leaving out any line which
+/// has been seen before, and return how many lines were left out.
+/// Lines are streamed, so large files are fine. Quits on any i/o error.
+///
+/// Only a 64-bit hash of each line is kept, not the line itself,
+/// so there is a tiny chance that two different lines have the same
+/// hash and the second is wrongly treated as a duplicate.
+pub fn dedup_lines<P: AsRef<Path>, Q: AsRef<Path>>(input: P, output: Q) -> usize {
+    use std::collections::HashSet;
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::Hasher;
+    let mut seen = HashSet::new();
+    let mut out = io::BufWriter::new(create(output));
+    let mut removed = 0;
+    for line in lines(open(input)) {
+        let mut hasher = DefaultHasher::new();
+        line.hash(&mut hasher);
+        if seen.insert(hasher.finish()) {
+            quit!(writeln!(out,"{}",line));
+        } else {
+            removed += 1;
+        }
+    }
+    quit!(out.flush());
+    removed
+}
+
+/// execute a shell command, combining stdout and stderr,
+/// and return the result as a string
+///
+/// ```
+/// extern crate easy_shortcuts as es;
+///
+/// let res = es::shell("rustc -V");
+/// assert!(res.starts_with("rustc"));
+/// ```
+pub fn shell(cmd: &str) -> String {
+    let o = Command::new(if cfg!(windows) {"cmd.exe"} else {"/bin/sh"})
+     .arg(if cfg!(windows) {"/c"} else {"-c"})
+     .arg(format!("{} 2>&1",cmd))
+     .output()
+     .expect("failed to execute shell");
+    String::from_utf8_lossy(&o.stdout).trim_end_matches('\n').to_string()
+}
+
+/// execute a shell command with the same standard input, output and
+/// error as this program, so that interactive programs work. Returns
+/// the exit code, which is -1 if the command was killed by a signal.
+/// Quits if the shell cannot be run.
+///
+/// ```
+/// extern crate easy_shortcuts as es;
+///
+/// assert_eq!(es::shell_interactive("exit 3"),3);
+/// ```
+pub fn shell_interactive(cmd: &str) -> i32 {
+    let status = Command::new(if cfg!(windows) {"cmd.exe"} else {"/bin/sh"})
+     .arg(if cfg!(windows) {"/c"} else {"-c"})
+     .arg(cmd)
+     .status();
+    match status {
+        Ok(status) => status.code().unwrap_or(-1),
+        Err(e) => quit(&format!("cannot run shell: {}",e))
+    }
+}
+
+/// execute a shell command like `shell`, and return the
+/// lines of output. No output gives an empty vector.
+///
+/// ```
+/// extern crate easy_shortcuts as es;
+///
+/// let res = es::shell_vec("echo one; echo two");
+/// assert_eq!(res,&["one","two"]);
+/// assert!(es::shell_vec("true").is_empty());
+/// ```
+pub fn shell_vec(cmd: &str) -> Vec<String> {
+    shell(cmd).lines().map(|s| s.to_string()).collect()
+}
+
+/// remembers the output of shell commands, so that running
+/// the same command again does not run it again. Only useful
+/// for commands which always give the same result.
+///
+/// ```
+/// extern crate easy_shortcuts as es;
+///
+/// let mut cache = es::ShellCache::new();
+/// assert_eq!(cache.run("echo hello"),"hello");
+/// ```
+#[derive(Debug,Default)]
+pub struct ShellCache {
+    outputs: HashMap<String,String>
+}
+
+impl ShellCache {
+    /// an empty cache.
+    pub fn new() -> ShellCache {
+        ShellCache::default()
+    }
+
+    /// the output of `shell(cmd)`, only running the command
+    /// the first time it is asked for.
+    pub fn run(&mut self, cmd: &str) -> &str {
+        self.outputs.entry(cmd.to_string()).or_insert_with(|| shell(cmd))
+    }
+}
+
+/// show text through a pager if standard output is a terminal.
+/// The pager is `$PAGER`, or `less` if that is not set. If stdout is
+/// not a terminal, or the pager cannot be run, the text is just printed.
+pub fn page(text: &str) {
+    use std::io::IsTerminal;
+    use std::process::Stdio;
+    if io::stdout().is_terminal() {
+        let pager = std::env::var("PAGER").unwrap_or_else(|_| "less".to_string());
+        let mut words = pager.split_whitespace();
+        if let Some(prog) = words.next() {
+            let child = Command::new(prog).args(words).stdin(Stdio::piped()).spawn();
+            if let Ok(mut child) = child {
+                if let Some(mut stdin) = child.stdin.take() {
+                    // the user may quit the pager early, so a broken pipe is fine
+                    let _ = stdin.write_all(text.as_bytes());
+                }
+                let _ = child.wait();
+                return;
+            }
+        }
+    }
+    print!("{}",text);
+    if ! text.ends_with('\n') {
+        println!();
+    }
+}
+
+/// implements line iterator over a readable.
+pub struct LineIter<R: io::Read> {
+    iter: io::Lines<io::BufReader<R>>
+}
+
+/// iterate over all lines from a readable.
+/// The iterator is over String (will quit if
+/// there is an i/o error)
+pub fn lines<R: io::Read>(f: R) -> LineIter<R> {
+    LineIter{iter: io::BufReader::new(f).lines()}
+}
+
+impl <R: io::Read> Iterator for LineIter<R> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        self.iter.next().map(|p| quit!(p))
+    }
+}
+
+/// implements the paragraph iterator created by `unwrap_paragraphs`
+pub struct ParagraphIter<I> {
+    iter: I,
+    blank: bool
+}
+
+impl <T,I> UnwrapParagraphs for I
+where T: AsRef<str>, I: Iterator<Item=T> {
+    fn unwrap_paragraphs(self) -> ParagraphIter<I> {
+        ParagraphIter{iter: self, blank: false}
+    }
+}
+
+impl <T,I> Iterator for ParagraphIter<I>
+where T: AsRef<str>, I: Iterator<Item=T> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        if self.blank {
+            self.blank = false;
+            return Some(String::new());
+        }
+        let mut para = String::new();
+        for line in self.iter.by_ref() {
+            let line = line.as_ref().trim();
+            if line.is_empty() {
+                // the blank line after a paragraph is returned next time
+                self.blank = ! para.is_empty();
+                return Some(para);
+            }
+            if ! para.is_empty() && ! para.ends_with('-') {
+                para.push(' ');
+            }
+            para.push_str(line);
+        }
+        if para.is_empty() { None } else { Some(para) }
+    }
+}
+
+/// iterate over the paragraphs of a readable, where paragraphs
+/// are separated by one or more blank lines. The lines of each
+/// paragraph are joined as with `unwrap_paragraphs` (will quit if
+/// there is an i/o error)
+///
+/// ```
+/// extern crate easy_shortcuts as es;
+///
+/// let text = "first line\nwrapped\n\n\nsecond\n";
+/// let paras: Vec<String> = es::paragraphs(text.as_bytes()).collect();
+/// assert_eq!(paras,&["first line wrapped","second"]);
+/// ```
+pub fn paragraphs<R: io::Read>(f: R) -> impl Iterator<Item=String> {
+    lines(f).unwrap_paragraphs().filter(|p| ! p.is_empty())
+}
+
+/// re-wrap text so that no line is longer than `width`, like `fmt`.
+/// The lines of each paragraph are joined and then filled with as
+/// many words as fit; paragraphs stay separated by a blank line.
+/// Each paragraph keeps the indentation of its first line, which is
+/// used for all of its lines. A word longer than the width gets
+/// a line to itself and is not broken.
+///
+/// ```
+/// extern crate easy_shortcuts as es;
+///
+/// let text = "the quick brown\nfox jumps over\nthe lazy dog\n\n  indented too\n";
+/// assert_eq!(es::reflow(text,20),"\
+/// the quick brown fox
+/// jumps over the lazy
+/// dog
+///
+///   indented too
+/// ");
+/// ```
+pub fn reflow(text: &str, width: usize) -> String {
+    let mut res = String::new();
+    let mut para = Vec::new();
+    // the extra blank line finishes the last paragraph
+    for line in text.lines().chain(Some("")) {
+        if ! line.trim().is_empty() {
+            para.push(line);
+        } else if ! para.is_empty() {
+            if ! res.is_empty() {
+                res.push('\n');
+            }
+            fill_paragraph(&para,width,&mut res);
+            para.clear();
+        }
+    }
+    res
+}
+
+fn fill_paragraph(lines: &[&str], width: usize, res: &mut String) {
+    let first = lines[0];
+    let indent = &first[..first.len() - first.trim_start().len()];
+    let room = width.saturating_sub(indent.display_width());
+    let mut line = String::new();
+    for word in lines.iter().flat_map(|l| l.split_whitespace()) {
+        if ! line.is_empty() && line.display_width() + 1 + word.display_width() > room {
+            *res += &format!("{}{}\n",indent,line);
+            line.clear();
+        }
+        if ! line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    *res += &format!("{}{}\n",indent,line);
+}
+
+/// implements the line iterator created by `input_lines`
+pub struct InputLines {
+    files: std::vec::IntoIter<String>,
+    stdin: Option<Box<dyn io::Read>>,
+    current: Option<LineIter<Box<dyn io::Read>>>
+}
+
+/// iterate over the lines of the files named on the command line,
+/// in order, in the manner of a Unix filter. A file named `-` means
+/// standard input, which is also read if there are no arguments.
+/// Will quit with the file name if a file cannot be opened.
+pub fn input_lines() -> InputLines {
+    InputLines::new(std::env::args().skip(1).collect(),Box::new(io::stdin()))
+}
+
+impl InputLines {
+    fn new(mut files: Vec<String>, stdin: Box<dyn io::Read>) -> InputLines {
+        if files.is_empty() {
+            files.push("-".to_string());
+        }
+        InputLines{files: files.into_iter(), stdin: Some(stdin), current: None}
+    }
+}
+
+impl Iterator for InputLines {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        loop {
+            if let Some(line) = self.current.as_mut().and_then(|c| c.next()) {
+                return Some(line);
+            }
+            let file = self.files.next()?;
+            let reader: Box<dyn io::Read> = if file == "-" {
+                // stdin can only be read once
+                match self.stdin.take() {
+                    Some(stdin) => stdin,
+                    None => continue
+                }
+            } else {
+                Box::new(open(&file))
+            };
+            self.current = Some(lines(reader));
+        }
+    }
+}
+
+/// implements the iterator created by `intersperse`
+pub struct Intersperse<I: Iterator> {
+    iter: std::iter::Peekable<I>,
+    sep: I::Item,
+    sep_next: bool
+}
+
+impl <I> Iterator for Intersperse<I>
+where I: Iterator, I::Item: Clone {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        if self.sep_next && self.iter.peek().is_some() {
+            self.sep_next = false;
+            Some(self.sep.clone())
+        } else {
+            self.sep_next = true;
+            self.iter.next()
+        }
+    }
+}
+
+/// implements the iterator created by `take_bytes`
+pub struct TakeBytesIter<I> {
+    iter: I,
+    left: usize,
+    done: bool
+}
+
+impl <T,I> TakeBytes for I
+where T: AsRef<str>, I: Iterator<Item=T> {
+    fn take_bytes(self, max: usize) -> TakeBytesIter<I> {
+        TakeBytesIter{iter: self, left: max, done: false}
+    }
+}
+
+impl <T,I> Iterator for TakeBytesIter<I>
+where T: AsRef<str>, I: Iterator<Item=T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.done {
+            return None;
+        }
+        let s = self.iter.next()?;
+        let len = s.as_ref().len();
+        if len > self.left {
+            // once over budget, stay finished
+            self.done = true;
+            None
+        } else {
+            self.left -= len;
+            Some(s)
+        }
+    }
+}
+
+// called by `DirIter` for entries which can't be read
+type ErrorCallback = Box<dyn FnMut(&Path,&io::Error) + Send>;
+
+// added by `filter_meta` and `filter_path`; all must pass
+type PathFilter = Box<dyn Fn(&Path,&fs::Metadata) -> bool + Send + Sync>;
+
+/// implements directory iterator over (path,metadata)
+/// created by `paths`
+pub struct DirIter {
+    iter: std::fs::ReadDir,
+    dir: path::PathBuf,
+    on_error: Option<ErrorCallback>,
+    filters: Vec<PathFilter>
+}
+
+
+impl Iterator for DirIter {
+    type Item = (path::PathBuf, fs::Metadata);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // the entry's path, if we got that far
+            let (path,res) = match self.iter.next()? {
+                Ok(entry) => (entry.path(),entry.metadata()),
+                Err(e) => (self.dir.clone(),Err(e))
+            };
+            match (res,&mut self.on_error) {
+                (Ok(meta),_) => if self.filters.iter().all(|f| f(&path,&meta)) {
+                    return Some((path,meta));
+                },
+                (Err(e),Some(f)) => f(&path,&e),
+                (Err(e),None) => quit_err(&e)
+            }
+        }
+    }
+}
+
+/// the kind of a directory entry. Symbolic links are
+/// distinguished from whatever they point to.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub enum FileKind {
+    File,
+    Dir,
+    Symlink,
+    Other
+}
+
+impl FileKind {
+    /// classify metadata, which should come from `symlink_metadata`
+    /// if links are to be recognized.
+    pub fn from_metadata(meta: &fs::Metadata) -> FileKind {
+        let ft = meta.file_type();
+        if ft.is_symlink() {
+            FileKind::Symlink
+        } else if ft.is_dir() {
+            FileKind::Dir
+        } else if ft.is_file() {
+            FileKind::File
+        } else {
+            FileKind::Other
+        }
+    }
+}
+
+/// implements directory iterator over (path,kind)
+/// created by `DirIter::kinds`
+pub struct KindIter {
+    iter: DirIter
+}
+
+impl DirIter {
+    /// instead of quitting when an entry can't be read, pass its path
+    /// and the error to `f` and carry on with the next entry. If the
+    /// entry's path isn't known, the directory's path is passed.
+    ///
+    /// ```
+    /// extern crate easy_shortcuts as es;
+    ///
+    /// let files = es::paths("src")
+    ///     .on_error(|path,e| eprintln!("skipping {}: {}",path.display(),e))
+    ///     .count();
+    /// assert!(files > 0);
+    /// ```
+    pub fn on_error<F: FnMut(&Path,&io::Error) + Send + 'static>(mut self, f: F) -> Self {
+        self.on_error = Some(Box::new(f));
+        self
+    }
+
+    /// only give entries whose metadata passes `f`, so there is
+    /// no need to match on the tuples in `filter`.
+    ///
+    /// ```
+    /// extern crate easy_shortcuts as es;
+    ///
+    /// for (path,meta) in es::paths(".").filter_meta(|m| m.is_file()) {
+    ///     println!("{} {}",path.display(),meta.len());
+    /// }
+    /// ```
+    pub fn filter_meta<F: Fn(&fs::Metadata) -> bool + Send + Sync + 'static>(mut self, f: F) -> Self {
+        self.filters.push(Box::new(move |_,m| f(m)));
+        self
+    }
+
+    /// only give entries whose path passes `f`.
+    ///
+    /// ```
+    /// extern crate easy_shortcuts as es;
+    ///
+    /// let manifests = es::paths(".").filter_path(|p| p.ends_with("Cargo.toml")).count();
+    /// assert_eq!(manifests,1);
+    /// ```
+    pub fn filter_path<F: Fn(&Path) -> bool + Send + Sync + 'static>(mut self, f: F) -> Self {
+        self.filters.push(Box::new(move |p,_| f(p)));
+        self
+    }
+
+    /// iterate over (path,kind) instead, where symbolic
+    /// links are reported as links and not followed.
+    /// Any filters and `on_error` callback still apply.
+    ///
+    /// ```
+    /// extern crate easy_shortcuts as es;
+    /// use es::FileKind;
+    ///
+    /// for (path,kind) in es::paths(".").kinds() {
+    ///     if path.ends_with("src") {
+    ///         assert_eq!(kind,FileKind::Dir);
+    ///     }
+    /// }
+    /// ```
+    pub fn kinds(self) -> KindIter {
+        KindIter{iter: self}
+    }
+}
+
+impl Iterator for KindIter {
+    type Item = (path::PathBuf, FileKind);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // the entry's metadata does not follow links
+        self.iter.next().map(|(path,meta)| (path,FileKind::from_metadata(&meta)))
+    }
+}
+
+/// implements directory iterator over filenames
+pub struct FileNameIter {
+    iter: std::fs::ReadDir
+}
+
+impl Iterator for FileNameIter {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.iter.next() {
+            None => None,
+            Some(me) => {
+                let entry = quit!(me);
+                let file = entry.file_name();
+                Some(file.to_string_lossy().to_string())
+            }
+        }
+    }
+
+}
+
+
+/// iterator over all entries in a directory.
+/// Returns a tuple of (`path::PathBuf`,`fs::Metadata`);
+/// will quit if the directory does not exist or there
+/// is an i/o error)
+pub fn paths<P: AsRef<Path>> (dir: P) -> DirIter {
+    match std::fs::read_dir(dir.as_ref()) {
+        Ok(s) => DirIter{iter: s, dir: dir.as_ref().to_path_buf(), on_error: None, filters: Vec::new()},
+        Err(e) => quit(&format!("{:?} {}",dir.as_ref(),e))
+    }
+}
+
+/// the entries of a directory in one pass, split into
+/// (directories, files, others). Symbolic links are not followed,
+/// so they go with the others; will quit on an i/o error.
+pub fn group_by_kind<P: AsRef<Path>>(dir: P) -> (Vec<path::PathBuf>,Vec<path::PathBuf>,Vec<path::PathBuf>) {
+    let (mut dirs, mut files, mut others) = (Vec::new(),Vec::new(),Vec::new());
+    for (path,kind) in paths(dir).kinds() {
+        match kind {
+            FileKind::Dir => dirs.push(path),
+            FileKind::File => files.push(path),
+            _ => others.push(path)
+        }
+    }
+    (dirs,files,others)
+}
+
+/// iterator over all files in a directory.
+/// Returns the files as strings;
+/// will quit if the directory does not exist or there
+/// is an i/o error)
+pub fn files<P: AsRef<Path>> (dir: P) -> FileNameIter {
+    match std::fs::read_dir(dir.as_ref()) {
+        Ok(s) => FileNameIter{iter: s},
+        Err(e) => quit(&format!("{:?} {}",dir.as_ref(),e))
+    }
+}
+
+/// all entries in a directory as (`path::PathBuf`,`fs::Metadata`),
+/// sorted with the comparison function `cmp`;
+/// will quit if the directory does not exist or there is an i/o error.
+///
+/// ```
+/// extern crate easy_shortcuts as es;
+///
+/// // largest first
+/// let entries = es::files_sorted_by("src",|a,b| b.1.len().cmp(&a.1.len()));
+/// assert!(entries[0].1.len() >= entries[1].1.len());
+/// ```
+pub fn files_sorted_by<P,F>(dir: P, cmp: F) -> Vec<(path::PathBuf,fs::Metadata)>
+where P: AsRef<Path>, F: FnMut(&(path::PathBuf,fs::Metadata),&(path::PathBuf,fs::Metadata)) -> std::cmp::Ordering {
+    let mut entries: Vec<_> = paths(dir).collect();
+    entries.sort_by(cmp);
+    entries
+}
+
+/// a simple listing of a directory as (name,size,is_dir),
+/// sorted by name. Directories have size 0;
+/// will quit if the directory does not exist or there is an i/o error.
+///
+/// ```
+/// extern crate easy_shortcuts as es;
+///
+/// for (name,size,is_dir) in es::list(".") {
+///     println!("{:20} {:>8}{}",name,size,if is_dir {"/"} else {""});
+/// }
+/// ```
+pub fn list<P: AsRef<Path>>(dir: P) -> Vec<(String,u64,bool)> {
+    let mut res: Vec<_> = paths(dir).map(|(p,m)| {
+        let name = p.file_name().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        let is_dir = m.is_dir();
+        (name,if is_dir {0} else {m.len()},is_dir)
+    }).collect();
+    res.sort();
+    res
+}
+
+/// look for a file called `name` in `dir`, and then in each
+/// of its parent directories in turn. Returns the full path of
+/// the first match. A relative `dir` is made absolute first, so the
+/// search always goes all the way up to the root; `None` if `dir`
+/// does not exist.
+///
+/// ```
+/// extern crate easy_shortcuts as es;
+///
+/// let manifest = es::find_up("src","Cargo.toml").unwrap();
+/// assert_eq!(manifest,std::fs::canonicalize("Cargo.toml").unwrap());
+/// ```
+pub fn find_up<P: AsRef<Path>>(dir: P, name: &str) -> Option<path::PathBuf> {
+    let start = fs::canonicalize(dir).ok()?;
+    let mut dir = start.as_path();
+    loop {
+        let file = dir.join(name);
+        if file.exists() {
+            return Some(file);
+        }
+        dir = dir.parent()?;
+    }
+}
+
+/// is `a` modified more recently than `b`? This is the make-style
+/// check for whether `b` needs rebuilding from `a`: if `b` does not
+/// exist, `a` is newer; if `a` does not exist, it is not.
+pub fn is_newer<P: AsRef<Path>, Q: AsRef<Path>>(a: P, b: Q) -> bool {
+    let modified = |p: &Path| fs::metadata(p).and_then(|m| m.modified()).ok();
+    match (modified(a.as_ref()),modified(b.as_ref())) {
+        (None,_) => false,
+        (Some(_),None) => true,
+        (Some(ta),Some(tb)) => ta > tb
+    }
+}
+
+/// all files below a directory, grouped by their extension.
+/// Files without an extension go into the `""` bucket. Like
+/// `all_paths`, hidden files and directories are skipped, and
+/// symbolic links are not followed or given; will quit if there
+/// is an i/o error.
+pub fn files_by_extension<P: AsRef<Path>>(dir: P) -> HashMap<String,Vec<path::PathBuf>> {
+    let mut res: HashMap<String,Vec<path::PathBuf>> = HashMap::new();
+    for (p,_) in all_paths(dir).files_only() {
+        let ext = p.extension()
+            .map(|e| e.to_string_lossy().to_string())
+            .unwrap_or_default();
+        res.entry(ext).or_default().push(p);
+    }
+    res
+}
+
+/// how many files below a directory have each extension, most
+/// common first. Files without an extension are counted under `""`.
+/// Like `all_paths`, hidden files and directories are skipped;
+/// will quit if there is an i/o error.
+///
+/// ```
+/// extern crate easy_shortcuts as es;
+///
+/// let counts = es::extension_counts("src");
+/// assert_eq!(counts[0].0,"rs");
+/// ```
+pub fn extension_counts<P: AsRef<Path>>(dir: P) -> Vec<(String,usize)> {
+    all_paths(dir).files_only().count_by_sorted(|(p,_)| {
+        p.extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_default()
+    })
+}
+
+/// bytes shown in the style of `xxd`: each line has the offset,
+/// sixteen bytes in hex in groups of two, and the bytes as ASCII,
+/// with anything unprintable shown as `.`.
+///
+/// ```
+/// extern crate easy_shortcuts as es;
+///
+/// assert_eq!(es::hexdump(b"hi\n"),"00000000: 6869 0a                                  hi.\n");
+/// ```
+pub fn hexdump(bytes: &[u8]) -> String {
+    let mut res = String::new();
+    for (i,chunk) in bytes.chunks(16).enumerate() {
+        let mut hex = String::new();
+        for (j,b) in chunk.iter().enumerate() {
+            if j > 0 && j % 2 == 0 {
+                hex.push(' ');
+            }
+            hex += &format!("{:02x}",b);
+        }
+        let ascii: String = chunk.iter()
+            .map(|&b| if b == b' ' || b.is_ascii_graphic() {b as char} else {'.'})
+            .collect();
+        res += &format!("{:08x}: {:<39}  {}\n",i*16,hex,ascii);
+    }
+    res
+}
+
+/// the contents of a file shown by `hexdump`, quitting if
+/// it can't be read.
+pub fn hexdump_file<P: AsRef<Path>>(file: P) -> String {
+    let mut bytes = Vec::new();
+    quit!(open(file).read_to_end(&mut bytes));
+    hexdump(&bytes)
+}
+
+/// watch the files below a directory, calling `f` with the files
+/// which changed each time some do. Every `interval` the modification
+/// times are compared with those from last time, so new, modified and
+/// deleted files are all reported. Hidden files are not watched.
+/// This never returns; will quit if the directory cannot be read,
+/// but not if something in it is deleted while it is being read.
+pub fn watch_dir<P: AsRef<Path>, F: FnMut(&[path::PathBuf])>(dir: P, interval: Duration, mut f: F) -> ! {
+    watch_dir_until(dir.as_ref(),interval,|changed| {
+        f(changed);
+        true
+    });
+    unreachable!();
+}
+
+// keep watching while `f` returns true
+fn watch_dir_until<F: FnMut(&[path::PathBuf]) -> bool>(dir: &Path, interval: Duration, mut f: F) {
+    // files are expected to come and go while the tree is read
+    let snapshot = || all_paths(dir).files_only().skip_vanished()
+        .map(|(p,m)| (p,m.modified().ok()))
+        .to_map();
+    let mut last: HashMap<_,_> = snapshot();
+    loop {
+        std::thread::sleep(interval);
+        let now = snapshot();
+        let mut changed = now.iter()
+            .filter(|&(p,t)| last.get(p) != Some(t))
+            .map(|(p,_)| p.clone())
+            .chain(last.keys().filter(|p| ! now.contains_key(*p)).cloned())
+            .to_vec();
+        last = now;
+        if ! changed.is_empty() {
+            changed.sort();
+            if ! f(&changed) {
+                return;
+            }
+        }
+    }
+}
+
+/// a number with its English ordinal suffix, like `1st` or `12th`.
+///
+/// ```
+/// extern crate easy_shortcuts as es;
+///
+/// assert_eq!(es::ordinal(22),"22nd");
+/// ```
+pub fn ordinal(n: u64) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_,11..=13) => "th",
+        (1,_) => "st",
+        (2,_) => "nd",
+        (3,_) => "rd",
+        _ => "th"
+    };
+    format!("{}{}",n,suffix)
+}
+
+
+/// a number with its digits in groups of three, separated by `sep`.
+///
+/// ```
+/// extern crate easy_shortcuts as es;
+///
+/// assert_eq!(es::group_digits(1234567,'_'),"1_234_567");
+/// ```
+pub fn group_digits(n: u64, sep: char) -> String {
+    let digits = n.to_string();
+    let mut res = String::new();
+    for (i,c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            res.push(sep);
+        }
+        res.push(c);
+    }
+    res
+}
+
+/// a number with commas as thousands separators.
+///
+/// ```
+/// extern crate easy_shortcuts as es;
+///
+/// assert_eq!(es::with_commas(1234567),"1,234,567");
+/// ```
+pub fn with_commas(n: u64) -> String {
+    group_digits(n,',')
+}
+
+/// a signed number with commas as thousands separators.
+///
+/// ```
+/// extern crate easy_shortcuts as es;
+///
+/// assert_eq!(es::with_commas_signed(-1234),"-1,234");
+/// ```
+pub fn with_commas_signed(n: i64) -> String {
+    let sign = if n < 0 {"-"} else {""};
+    format!("{}{}",sign,with_commas(n.unsigned_abs()))
+}
+
+// directories for the tests of all the modules to play in. They are
+// removed when the guard is dropped, even if the test fails.
+#[cfg(test)]
+mod testing {
+    use std::fs;
+    use TempDir;
+
+    // a fresh, empty directory
+    pub fn scratch_dir(name: &str) -> TempDir {
+        ::temp_dir_guard(&format!("easy-shortcuts-{}",name))
+    }
+
+    // a fresh directory containing the given empty files, which may be nested
+    pub fn scratch_tree(name: &str, files: &[&str]) -> TempDir {
+        let dir = scratch_dir(name);
+        for file in files {
+            let path = dir.path().join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            ::write_all(path,"");
+        }
+        dir
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use traits::*;
+    use testing::scratch_dir;
+    use std::env;
+    use std::fs;
+    use std::process::{Command,Output};
+    use std::error::Error as StdError;
+
+    // Tests of quitting need a separate process. The `child_` tests only do
+    // their thing when this test executable runs them as a child process.
+    fn run_child(test: &str) -> Output {
+        run_child_with_input(test,"")
+    }
+
+    fn run_child_with_input(test: &str, input: &str) -> Output {
+        use std::io::Write;
+        use std::process::Stdio;
+        let mut child = Command::new(env::current_exe().unwrap())
+            .args(["--exact",&format!("tests::{}",test),"--nocapture","--test-threads=1"])
+            .env("EASY_CHILD_TEST","1")
+            .env_remove("EASY_DONT_QUIT_PANIC")
+            .stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped())
+            .spawn().unwrap();
+        child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+        child.wait_with_output().unwrap()
+    }
+
+    fn is_child() -> bool {
+        env::var("EASY_CHILD_TEST").is_ok()
+    }
+
+    #[test]
+    fn test_to_vec() {
+        let v = "one two three".split_whitespace().to_vec();
+        assert_eq!(v,&["one","two","three"]);
+    }
+
+    #[test]
+    #[allow(array_into_iter,clippy::into_iter_on_ref)]
+    fn test_iter_join() {
+        // join an iterator over &str
+        let v = ["one","two","three"];
+        let s = v.into_iter().join(',');
+        assert_eq!(s,"one,two,three");
+
+        // join an iterator over String
+        let v = vec!["one".to_string(),"two".to_string(),"three".to_string()];
+        let s = v.into_iter().map(|s| s.to_uppercase()).join(',');
+        assert_eq!(s,"ONE,TWO,THREE");
+
+        let s = ["one","two","three"].into_iter().prepend(" -L");
+        assert_eq!(s," -Lone -Ltwo -Lthree");
+
+        assert_eq!(["x y","z"].iter().wrap_each("\"","\" "),"\"x y\" \"z\" ");
+        assert_eq!(Vec::<&str>::new().iter().wrap_each("<",">"),"");
+
+        let s = ["a","b","c"].iter().append_indexed(|i,s| if i % 2 == 0 {s.to_uppercase()} else {s.to_string()});
+        assert_eq!(s,"AbC");
+        assert_eq!(Vec::<String>::new().iter().append_indexed(|_,s| s.to_string()),"");
+
+        // join_fold can do a different separator before the last item
+        let n = 3;
+        let s = ["a","b","c"].iter().enumerate().map(|(i,s)| if i + 1 == n {format!("and {}",s)} else {s.to_string()})
+            .join_fold(|acc,item,first| {
+                if ! first { acc.push_str(", "); }
+                acc.push_str(item);
+            });
+        assert_eq!(s,"a, b, and c");
+        assert_eq!(Vec::<&str>::new().iter().join_fold(|acc,item,_| acc.push_str(item)),"");
+
+        assert_eq!(["one","two"].iter().join_str(", "),"one, two");
+        assert_eq!(["one"].iter().join_str(", "),"one");
+        assert_eq!(Vec::<String>::new().iter().join_str(", "),"");
+        assert_eq!(["",""].iter().join_str("::"),"::");
+        assert_eq!(["one","two"].iter().concat_strings(),"onetwo");
+        assert_eq!(Vec::<String>::new().iter().concat_strings(),"");
+        let mut buf = "start:".to_string();
+        ["a","b"].iter().join_to(',',&mut buf);
+        assert_eq!(buf,"start:a,b");
+        Vec::<&str>::new().iter().join_to(',',&mut buf);
+        assert_eq!(buf,"start:a,b");
+
+        assert_eq!(["a","","b"].iter().join_non_empty(','),"a,b");
+        assert_eq!(["","a","b",""].iter().join_non_empty(','),"a,b");
+        assert_eq!(["a"," ","b"].iter().join_non_empty(','),"a, ,b");
+        assert_eq!(["",""].iter().join_non_empty(','),"");
+        assert_eq!(["a"," ","\t","b",""].iter().join_non_blank(','),"a,b");
+        assert_eq!([" x "," "].iter().join_non_blank(',')," x ");
+
+        assert_eq!(["plain","","two\nlines","cr\r"," spaced "].iter().csv_line(),
+            "plain,,\"two\nlines\",\"cr\r\", spaced ");
+        assert_eq!(["\""].iter().csv_line(),"\"\"\"\"");
+        assert_eq!(Vec::<&str>::new().iter().csv_line(),"");
+
+        let args = ["a b","c'd","","$HOME"].iter().quote_join(' ');
+        assert_eq!(args,r"'a b' 'c'\''d' '' '$HOME'");
+        #[cfg(unix)]
+        assert_eq!(::shell(&format!("printf '%s|' {}",args)),"a b|c'd||$HOME|");
+
+        let words = "one two".split_whitespace().map(|s| s.to_string());
+        let line: String = Join::intersperse(words," ".to_string())
+            .chain(::std::iter::once("\n".to_string())).collect();
+        assert_eq!(line,"one two\n");
+        assert_eq!(Join::intersperse(["x"].iter(),&"-").to_vec(),&[&"x"]);
+        assert!(Join::intersperse(Vec::<&str>::new().into_iter(),"-").next().is_none());
+    }
+
+    #[test]
+    fn test_files_by_extension() {
+        let tmp = scratch_dir("by-ext");
+        let dir = tmp.path();
+        fs::create_dir(dir.join("sub")).unwrap();
+        for f in &["one.rs","two.txt","sub/three.rs","README",".hidden.rs"] {
+            ::write_all(dir.join(f),"");
+        }
+        let map = ::files_by_extension(dir);
+        let mut rs = map["rs"].clone();
+        rs.sort();
+        assert_eq!(rs,vec![dir.join("one.rs"),dir.join("sub/three.rs")]);
+        assert_eq!(map["txt"],vec![dir.join("two.txt")]);
+        assert_eq!(map[""],vec![dir.join("README")]);
+        assert_eq!(map.len(),3);
+    }
+
+    #[test]
+    fn test_expand_args() {
+        let tmp = scratch_dir("args");
+        let dir = tmp.path();
+        let file = dir.join("args.txt");
+        ::write_all(&file,"-v --out\n  result.txt\n");
+        let args = vec!["prog".to_string(),format!("@{}",file.display()),"last".to_string(),"@".to_string()];
+        let res = ::expand_args(args.into_iter());
+        assert_eq!(res,&["prog","-v","--out","result.txt","last","@"]);
+    }
+
+    #[test]
+    fn child_print_vec() {
+        if ! is_child() { return; }
+        let words = "one two three".split_whitespace().map(|s| s.to_string()).print_vec(",");
+        assert_eq!(words,&["one","two","three"]);
+        println!();
+    }
+
+    #[test]
+    fn test_print_vec() {
+        let out = run_child("child_print_vec");
+        assert!(out.status.success());
+        assert!(String::from_utf8_lossy(&out.stdout).contains("one,two,three,\n"));
+    }
+
+    #[test]
+    fn child_poisoned_lock() {
+        if ! is_child() { return; }
+        use std::sync::{Arc,Mutex};
+        use std::thread;
+        let m = Arc::new(Mutex::new(0));
+        let m2 = m.clone();
+        let _ = thread::spawn(move || {
+            let _guard = m2.lock().unwrap();
+            panic!("poisoning the lock");
+        }).join();
+        let _n = m.lock().or_die("lock");
+        unreachable!();
+    }
+
+    #[test]
+    fn test_poisoned_lock_quits() {
+        let out = run_child("child_poisoned_lock");
+        let err = String::from_utf8_lossy(&out.stderr);
+        assert_eq!(out.status.code(),Some(1));
+        assert!(err.contains("error: lock poisoned lock"),"{}",err);
+    }
+
+    #[test]
+    fn child_usage() {
+        if ! is_child() { return; }
+        ::usage("usage: prog <file>");
+    }
+
+    #[test]
+    fn test_usage_exit_code() {
+        let out = run_child("child_usage");
+        assert_eq!(out.status.code(),Some(2));
+        assert_eq!(String::from_utf8_lossy(&out.stderr),"usage: prog <file>\n");
+    }
+
+    #[test]
+    fn child_quit_flushes() {
+        if ! is_child() { return; }
+        use std::io::Write;
+        // no newline, so this stays in the stdout buffer
+        let mut out = ::std::io::stdout();
+        write!(out,"pending output").unwrap();
+        ::quit("bad");
+    }
+
+    #[test]
+    fn test_quit_flushes() {
+        let out = run_child("child_quit_flushes");
+        assert_eq!(out.status.code(),Some(1));
+        assert!(String::from_utf8_lossy(&out.stdout).contains("pending output"));
+        assert!(String::from_utf8_lossy(&out.stderr).ends_with("error: bad\n"));
+    }
+
+    #[test]
+    fn test_input_lines() {
+        let tmp = scratch_dir("input-lines");
+        let dir = tmp.path();
+        let one = dir.join("one.txt").to_string_lossy().to_string();
+        let two = dir.join("two.txt").to_string_lossy().to_string();
+        ::write_all(&one,"a\nb\n");
+        ::write_all(&two,"c\n");
+        let stdin = || Box::new(::std::io::Cursor::new("in1\nin2\n"));
+
+        let res = ::InputLines::new(vec![],stdin()).to_vec();
+        assert_eq!(res,&["in1","in2"]);
+
+        let res = ::InputLines::new(vec![one.clone(),two.clone()],stdin()).to_vec();
+        assert_eq!(res,&["a","b","c"]);
+
+        let args = vec![two,"-".to_string(),one,"-".to_string()];
+        let res = ::InputLines::new(args,stdin()).to_vec();
+        assert_eq!(res,&["c","in1","in2","a","b"]);
+    }
+
+    #[test]
+    fn child_stdin_to_stdout() {
+        if ! is_child() { return; }
+        use std::io::{Read,Write};
+        let mut text = String::new();
+        ::open_or_stdin("-").read_to_string(&mut text).unwrap();
+        write!(::create_or_stdout("-"),"[{}]",text.trim()).unwrap();
+    }
+
+    #[test]
+    fn test_open_or_stdin() {
+        use std::io::{Read,Write};
+        let out = run_child_with_input("child_stdin_to_stdout","piped");
+        assert!(String::from_utf8_lossy(&out.stdout).contains("[piped]"));
+
+        let tmp = scratch_dir("open-or-stdin");
+        let dir = tmp.path();
+        let file = dir.join("out.txt").to_string_lossy().to_string();
+        write!(::create_or_stdout(&file),"hello").unwrap();
+        let mut text = String::new();
+        ::open_or_stdin(&file).read_to_string(&mut text).unwrap();
+        assert_eq!(text,"hello");
+    }
+
+    #[test]
+    fn test_is_newer() {
+        use std::path::Path;
+        use std::thread;
+        use std::time::Duration;
+        let tmp = scratch_dir("newer");
+        let dir = tmp.path();
+        let src = dir.join("src.txt");
+        let out = dir.join("out.txt");
+        ::write_all(&src,"source");
+        assert!(::is_newer(&src,&out));
+        assert!(! ::is_newer(&out,&src));
+
+        // write the output until the file system sees it as newer
+        let modified = |p: &Path| fs::metadata(p).unwrap().modified().unwrap();
+        ::write_all(&out,"output");
+        while modified(&out) <= modified(&src) {
+            thread::sleep(Duration::from_millis(10));
+            ::write_all(&out,"output");
+        }
+        assert!(! ::is_newer(&src,&out));
+        assert!(::is_newer(&out,&src));
+    }
+
+    #[test]
+    fn test_numeric_predicates() {
+        for s in &["0","-12","+7"," 42\t"] {
+            assert!(s.is_integer() && s.is_float() && s.is_numeric(),"{}",s);
+        }
+        for s in &["1.5","-0.25"," 1e10 ",".5"] {
+            assert!(! s.is_integer() && s.is_float() && s.is_numeric(),"{}",s);
+        }
+        for s in &["","  ","abc","1.2.3","12a","inf","NaN","- 1"] {
+            assert!(! s.is_integer() && ! s.is_float() && ! s.is_numeric(),"{}",s);
+        }
+    }
+
+    #[test]
+    fn test_to_bool() {
+        for s in &["true","t","yes","y","on","1","TRUE"," Yes ","On"] {
+            assert_eq!(s.to_bool(),Some(true),"{}",s);
+        }
+        for s in &["false","f","no","n","off","0","False","NO"] {
+            assert_eq!(s.to_bool(),Some(false),"{}",s);
+        }
+        for s in &["","2","yep","nope","truee"] {
+            assert_eq!(s.to_bool(),None,"{}",s);
+        }
+    }
+
+    #[test]
+    fn child_page() {
+        if ! is_child() { return; }
+        ::page("paged\ntext");
+    }
+
+    #[test]
+    fn test_page_not_a_terminal() {
+        let out = run_child("child_page");
+        assert!(String::from_utf8_lossy(&out.stdout).contains("paged\ntext\n"));
+    }
+
+    #[test]
+    fn test_retry() {
+        use std::time::Duration;
+        use std::io::{Error,ErrorKind};
+        let delay = Duration::from_millis(1);
+        let failing = |n: u32, kind: ErrorKind| {
+            let mut calls = 0;
+            move || {
+                calls += 1;
+                if calls <= n { Err(Error::from(kind)) } else { Ok(calls) }
+            }
+        };
+        // transient errors are retried until success
+        let res = ::retry(4,delay,failing(3,ErrorKind::PermissionDenied),::is_transient);
+        assert_eq!(res.unwrap(),4);
+        // but not beyond the number of tries
+        let res = ::retry(3,delay,failing(3,ErrorKind::PermissionDenied),::is_transient);
+        assert_eq!(res.unwrap_err().kind(),ErrorKind::PermissionDenied);
+        // and other errors fail at once
+        let res = ::retry(4,delay,failing(1,ErrorKind::NotFound),::is_transient);
+        assert_eq!(res.unwrap_err().kind(),ErrorKind::NotFound);
+
+        let tmp = scratch_dir("retry");
+        let dir = tmp.path();
+        ::write_all(dir.join("file"),"ok");
+        ::open_retry(dir.join("file"),3,delay);
+    }
+
+    #[test]
+    fn test_read_cached() {
+        let tmp = scratch_dir("cached");
+        let dir = tmp.path();
+        let file = dir.join("template.txt");
+        ::write_all(&file,"first");
+        assert_eq!(::read_cached(&file),"first");
+        assert_eq!(::read_cached(&file),"first");
+
+        // a different size, so the change is seen whatever the modification time
+        ::write_all(&file,"second");
+        assert_eq!(::read_cached(&file),"second");
+        assert_eq!(::read_cached(dir.join(".").join("template.txt")),"second");
+    }
+
+    #[test]
+    fn child_read_cached_in_quit_handler() {
+        if ! is_child() { return; }
+        let tmp = scratch_dir("cached-quit");
+        let dir = tmp.path().to_path_buf();
+        ::write_all(dir.join("message.txt"),"from the cache");
+        let message = dir.join("message.txt");
+        ::set_quit_handler(Box::new(move |_| println!("{}",::read_cached(&message))));
+        // quits while reading, after finding it is not cached
+        fs::create_dir(dir.join("not-a-file")).unwrap();
+        ::read_cached(dir.join("not-a-file"));
+    }
+
+    #[test]
+    fn test_read_cached_in_quit_handler() {
+        let out = run_child("child_read_cached_in_quit_handler");
+        assert_eq!(out.status.code(),Some(1));
+        assert!(String::from_utf8_lossy(&out.stdout).contains("from the cache"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_kinds() {
+        use ::FileKind;
+        let tmp = scratch_dir("kinds");
+        let dir = tmp.path();
+        ::write_all(dir.join("file"),"");
+        fs::create_dir(dir.join("subdir")).unwrap();
+        ::std::os::unix::fs::symlink(dir.join("subdir"),dir.join("link")).unwrap();
+        let mut kinds = ::paths(dir).kinds()
+            .map(|(p,k)| (p.file_name().unwrap().to_string_lossy().to_string(),k))
+            .to_vec();
+        kinds.sort_by(|a,b| a.0.cmp(&b.0));
+        assert_eq!(kinds,vec![
+            ("file".to_string(),FileKind::File),
+            ("link".to_string(),FileKind::Symlink),
+            ("subdir".to_string(),FileKind::Dir)
+        ]);
+
+        // filters apply to the kinds too, and see the link itself
+        let kinds = ::paths(dir).filter_path(|p| ! p.ends_with("file"))
+            .filter_meta(|m| ! m.is_dir())
+            .kinds()
+            .to_vec();
+        assert_eq!(kinds,vec![(dir.join("link"),FileKind::Symlink)]);
+    }
+
+    #[test]
+    fn test_truncate_ellipsis() {
+        assert_eq!("abcdef".truncate_ellipsis(6),"abcdef");
+        assert_eq!("abcdef".truncate_ellipsis(5),"abcd…");
+        assert_eq!("abcdef".truncate_ellipsis(1),"…");
+        assert_eq!("abcdef".truncate_ellipsis(0),"");
+    }
+
+    #[test]
+    fn test_truncate_middle() {
+        let path = "/usr/local/share/doc/easy-shortcuts/README.md";
+        let short = path.truncate_middle(20);
+        assert_eq!(short,"/usr/local…README.md");
+        assert_eq!(short.chars().count(),20);
+        assert!(short.starts_with("/usr/") && short.ends_with("README.md"));
+        assert_eq!("abcdef".truncate_middle(6),"abcdef");
+        assert_eq!("abcdef".truncate_middle(5),"ab…ef");
+        assert_eq!("abcdef".truncate_middle(4),"ab…f");
+        assert_eq!("abcdef".truncate_middle(2),"a…");
+        assert_eq!("abcdef".truncate_middle(1),"…");
+        assert_eq!("abcdef".truncate_middle(0),"");
+        assert_eq!("héllo wörld".truncate_middle(7),"hél…rld");
+    }
+
+    #[cfg(feature = "unicode")]
+    #[test]
+    fn test_display_width() {
+        assert_eq!("日本語".display_width(),6);
+        assert_eq!("e\u{301}".display_width(),1);
+        assert_eq!("日本".pad_right(6),"日本  ");
+        assert_eq!("日本".pad_left(5)," 日本");
+        assert_eq!("日本語".truncate_ellipsis(5),"日本…");
+        assert_eq!("日本語".truncate_ellipsis(4),"日…");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_group_by_kind() {
+        let tmp = scratch_dir("group-kind");
+        let dir = tmp.path();
+        ::write_all(dir.join("a.txt"),"");
+        ::write_all(dir.join("b.txt"),"");
+        fs::create_dir(dir.join("sub")).unwrap();
+        ::std::os::unix::fs::symlink(dir.join("a.txt"),dir.join("link")).unwrap();
+        let (dirs,mut files,others) = ::group_by_kind(dir);
+        files.sort();
+        assert_eq!(dirs,vec![dir.join("sub")]);
+        assert_eq!(files,vec![dir.join("a.txt"),dir.join("b.txt")]);
+        assert_eq!(others,vec![dir.join("link")]);
+    }
+
+    #[derive(Debug)]
+    struct ConfigError(::std::io::Error);
+
+    impl ::std::fmt::Display for ConfigError {
+        fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+            write!(f,"cannot load config")
+        }
+    }
+
+    impl ::std::error::Error for ConfigError {
+        fn source(&self) -> Option<&(dyn StdError + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    #[test]
+    fn child_quit_err_code() {
+        if ! is_child() { return; }
+        let inner = ::std::io::Error::new(::std::io::ErrorKind::NotFound,"no such file");
+        let res: Result<(),ConfigError> = Err(ConfigError(inner));
+        quit!(res,3);
+    }
+
+    #[test]
+    fn test_quit_err_code() {
+        let out = run_child("child_quit_err_code");
+        assert_eq!(out.status.code(),Some(3));
+        let err = String::from_utf8_lossy(&out.stderr);
+        assert!(err.ends_with("error: cannot load config: no such file\n"),"{}",err);
+    }
+
+    #[test]
+    fn test_ordinal() {
+        let res = [0,1,2,3,4,11,12,13,21,101,111,112,1003].iter().map(|&n| ::ordinal(n)).to_vec();
+        assert_eq!(res,&["0th","1st","2nd","3rd","4th","11th","12th","13th","21st","101st","111th","112th","1003rd"]);
+    }
+
+    #[test]
+    fn test_with_commas() {
+        assert_eq!(::with_commas(0),"0");
+        assert_eq!(::with_commas(999),"999");
+        assert_eq!(::with_commas(1000),"1,000");
+        assert_eq!(::with_commas(123456),"123,456");
+        assert_eq!(::with_commas(u64::MAX),"18,446,744,073,709,551,615");
+        assert_eq!(::with_commas_signed(-999),"-999");
+        assert_eq!(::with_commas_signed(-1000),"-1,000");
+        assert_eq!(::with_commas_signed(i64::MIN),"-9,223,372,036,854,775,808");
+        assert_eq!(::group_digits(1234567,' '),"1 234 567");
+    }
+
+    #[test]
+    fn test_concat_files() {
+        let tmp = scratch_dir("concat");
+        let dir = tmp.path();
+        let one = dir.join("one.txt");
+        let two = dir.join("two.txt");
+        ::write_all(&one,"alpha\nbeta\n");
+        ::write_all(&two,"gamma\n");
+        let out = dir.join("report.txt");
+        ::concat_files(&[&one,&two],&out,Some("== {} =="));
+        assert_eq!(::read_to_string(&out),format!(
+            "== {} ==\nalpha\nbeta\n== {} ==\ngamma\n",one.display(),two.display()
+        ));
+    }
+
+    #[test]
+    fn child_print_peek() {
+        if ! is_child() { return; }
+        let mut iter = (10..12).peekable();
+        iter.print_peek();
+        iter.print_peek();
+        assert_eq!(iter.next(),Some(10));
+        iter.next();
+        iter.print_peek();
+    }
+
+    #[test]
+    fn test_print_peek() {
+        let out = run_child("child_print_peek");
+        assert!(out.status.success());
+        assert!(String::from_utf8_lossy(&out.stderr).starts_with("10\n10\n<end>\n"));
+    }
+
+    #[test]
+    fn test_write_verified() {
+        let tmp = scratch_dir("verified");
+        let dir = tmp.path();
+        let file = dir.join("state.txt");
+        ::write_verified(&file,"important\nstate\n");
+        assert_eq!(::read_to_string(&file),"important\nstate\n");
+        ::write_verified(&file,"");
+        assert_eq!(::read_to_string(&file),"");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_shell_cache() {
+        let mut cache = ::ShellCache::new();
+        let first = cache.run("date +%N").to_string();
+        assert_ne!(::shell("sleep 0.01; date +%N"),first);
+        assert_eq!(cache.run("date +%N"),first);
+        assert_eq!(cache.run("echo other"),"other");
+    }
+
+    #[test]
+    fn test_format_columns() {
+        let items = "one two three four five six seven".split_whitespace().map(|s| s.to_string()).to_vec();
+        assert_eq!(::format_columns(&items,30),"\
+one  three  five  seven
+two  four   six
+");
+        assert_eq!(::format_columns(&items,20),"\
+one    four  seven
+two    five
+three  six
+");
+        assert_eq!(::format_columns(&items,5),"one\ntwo\nthree\nfour\nfive\nsix\nseven\n");
+        let wide = vec!["a".to_string(),"far too wide".to_string(),"b".to_string()];
+        assert_eq!(::format_columns(&wide,8),"a\nfar too wide\nb\n");
+        assert_eq!(::format_columns(&[],80),"");
+    }
+
+    #[test]
+    fn test_temp_dir_guard() {
+        let tmp = ::temp_dir_guard("guard");
+        let path = tmp.path().to_path_buf();
+        assert!(path.is_dir());
+        fs::create_dir(path.join("sub")).unwrap();
+        ::write_all(path.join("sub/file.txt"),"scratch");
+        let other = ::temp_dir_guard("guard");
+        assert_ne!(other.path(),tmp.path());
+        drop(tmp);
+        assert!(! path.exists());
+
+        let kept = other.keep();
+        assert!(kept.is_dir());
+        fs::remove_dir_all(&kept).unwrap();
+    }
+
+    #[test]
+    fn child_files_and_dirs_only() {
+        if ! is_child() { return; }
+        let _ = ::all_paths(".").files_only().dirs_only();
+    }
+
+    #[test]
+    fn test_files_and_dirs_only_quits() {
+        let out = run_child("child_files_and_dirs_only");
+        assert_eq!(out.status.code(),Some(1));
+        assert!(String::from_utf8_lossy(&out.stderr).contains("both files_only and dirs_only"));
+    }
+
+    #[test]
+    fn test_similarity() {
+        assert_eq!("".levenshtein("abc"),3);
+        assert_eq!("abc".levenshtein(""),3);
+        assert_eq!("flaw".levenshtein("lawn"),2);
+        assert_eq!("café".levenshtein("cafe"),1);
+
+        assert_eq!("rust".similarity("rust"),1.0);
+        assert_eq!("".similarity(""),1.0);
+        assert_eq!("abc".similarity("xyz"),0.0);
+        assert_eq!("abc".similarity(""),0.0);
+        assert_eq!("hello".similarity("hallo"),0.8);
+        assert!("apple".similarity("apply") > "apple".similarity("maple"));
+    }
+
+    #[test]
+    fn test_hexdump() {
+        let bytes = b"hello\n\x00\x01\xffABCDEFGHIJKLMNOP";
+        assert_eq!(::hexdump(bytes),"\
+00000000: 6865 6c6c 6f0a 0001 ff41 4243 4445 4647  hello....ABCDEFG
+00000010: 4849 4a4b 4c4d 4e4f 50                   HIJKLMNOP
+");
+        assert_eq!(::hexdump(b""),"");
+
+        let tmp = scratch_dir("hexdump");
+        let dir = tmp.path();
+        fs::write(dir.join("bin"),bytes).unwrap();
+        assert_eq!(::hexdump_file(dir.join("bin")),::hexdump(bytes));
+    }
+
+    #[test]
+    fn test_shell_interactive() {
+        assert_eq!(::shell_interactive("true"),0);
+        assert_eq!(::shell_interactive("exit 7"),7);
+    }
+
+    #[test]
+    fn test_frequencies() {
+        let freqs = "naïve café ñ".char_frequencies();
+        assert_eq!(freqs[&'ï'],1);
+        assert_eq!(freqs[&'a'],2);
+        assert_eq!(freqs[&' '],2);
+        assert_eq!(freqs.values().sum::<usize>(),"naïve café ñ".char_len());
+        assert!("".char_frequencies().is_empty());
+
+        let words = "état  Été\nétat".word_frequencies();
+        assert_eq!(words["état"],2);
+        assert_eq!(words["Été"],1);
+        assert_eq!(words.len(),2);
+    }
+
+    #[test]
+    fn test_paragraphs() {
+        let tmp = scratch_dir("paragraphs");
+        let dir = tmp.path();
+        let file = dir.join("prose.txt");
+        ::write_all(&file,"\n\nIt was a dark\nand stormy night.\n\nSuddenly,\n  a shot\nrang out!\n\n\n   \nThe end.");
+        let paras = ::paragraphs(::open(&file)).to_vec();
+        assert_eq!(paras,&["It was a dark and stormy night.","Suddenly, a shot rang out!","The end."]);
+        assert_eq!(::paragraphs("\n\n".as_bytes()).count(),0);
+    }
+
+    #[test]
+    fn test_dedup_lines() {
+        let tmp = scratch_dir("dedup");
+        let dir = tmp.path();
+        let input = dir.join("in.txt");
+        let output = dir.join("out.txt");
+        ::write_all(&input,"b\na\nb\n\nc\na\n\nb");
+        assert_eq!(::dedup_lines(&input,&output),4);
+        assert_eq!(::read_to_string(&output),"b\na\n\nc\n");
+        ::write_all(&input,"");
+        assert_eq!(::dedup_lines(&input,&output),0);
+        assert_eq!(::read_to_string(&output),"");
+    }
+
+    #[test]
+    fn test_reflow() {
+        let text = "Lorem ipsum dolor\nsit amet,   consectetur\n\n\n\n    adipiscing elit, sed do\n  eiusmod tempor\n";
+        assert_eq!(::reflow(text,16),"\
+Lorem ipsum
+dolor sit amet,
+consectetur
+
+    adipiscing
+    elit, sed do
+    eiusmod
+    tempor
+");
+        assert_eq!(::reflow("a supercalifragilistic word",10),"a\nsupercalifragilistic\nword\n");
+        assert_eq!(::reflow("\n\n",10),"");
+        assert_eq!(::reflow("one two",80),"one two\n");
+    }
+
+    #[test]
+    fn test_split_camel() {
+        assert_eq!("camelCase".split_camel(),&["camel","Case"]);
+        assert_eq!("PascalCase".split_camel(),&["Pascal","Case"]);
+        assert_eq!("HTTPSConnection".split_camel(),&["HTTPS","Connection"]);
+        assert_eq!("getHTTPResponseCode".split_camel(),&["get","HTTP","Response","Code"]);
+        assert_eq!("HTTP2Server".split_camel(),&["HTTP2","Server"]);
+        assert_eq!("snake_case".split_camel(),&["snake_case"]);
+        assert_eq!("ABC".split_camel(),&["ABC"]);
+        assert_eq!("x".split_camel(),&["x"]);
+        assert!("".split_camel().is_empty());
+    }
+
+    #[test]
+    fn test_watch_dir() {
+        use std::sync::mpsc;
+        use std::thread;
+        use std::time::Duration;
+        let tmp = scratch_dir("watch");
+        let dir = tmp.path();
+        ::write_all(dir.join("old.txt"),"old");
+        ::write_all(dir.join("gone.txt"),"gone");
+        let (tx,rx) = mpsc::channel();
+        let watched = dir.to_path_buf();
+        let watcher = thread::spawn(move || {
+            // the two changes may be seen in different polls
+            let mut seen = Vec::new();
+            ::watch_dir_until(&watched,Duration::from_millis(20),|changed| {
+                seen.extend_from_slice(changed);
+                if seen.len() < 2 {
+                    return true;
+                }
+                seen.sort();
+                tx.send(seen.clone()).unwrap();
+                false
+            });
+        });
+        thread::sleep(Duration::from_millis(50));
+        ::write_all(dir.join("new.txt"),"new");
+        fs::remove_file(dir.join("gone.txt")).unwrap();
+        let changed = rx.recv_timeout(Duration::from_secs(5)).expect("no change reported");
+        assert_eq!(changed,vec![dir.join("gone.txt"),dir.join("new.txt")]);
+        watcher.join().unwrap();
     }
-}
 
-impl <T,I> PrintDbg for I
-where T: Debug, I: Iterator<Item=T> {
-    fn debug(self,delim: &str) {
-        for v in self {
-            print!("{:?}{}",v,delim);
+    #[test]
+    fn test_watch_dir_deleting() {
+        use std::thread;
+        use std::time::Duration;
+        let tmp = scratch_dir("watch-deleting");
+        let dir = tmp.path().to_path_buf();
+        let watched = dir.clone();
+        // polling as fast as possible, so directories are often removed
+        // while a snapshot is being taken
+        let watcher = thread::spawn(move || {
+            ::watch_dir_until(&watched,Duration::from_millis(1),|changed| {
+                ! changed.iter().any(|p| p.ends_with("done.txt"))
+            });
+        });
+        for i in 0..200 {
+            let sub = dir.join(format!("sub{}",i % 5)).join("deeper");
+            fs::create_dir_all(&sub).unwrap();
+            ::write_all(sub.join("file.txt"),"");
+            fs::remove_dir_all(sub.parent().unwrap()).unwrap();
         }
+        ::write_all(dir.join("done.txt"),"");
+        watcher.join().unwrap();
     }
-}
 
-impl MetadataLike for io::Result<fs::Metadata> {
-    fn is_dir(self) -> bool {
-        match self {
-            Ok(meta) => meta.is_dir(),
-            Err(_) => false
+    #[test]
+    fn test_case_conversion() {
+        for s in &["HelloWorld","helloWorld","hello_world","hello-world","hello world","__hello__world"] {
+            assert_eq!(s.to_snake_case(),"hello_world");
+            assert_eq!(s.to_camel_case(),"helloWorld");
         }
+        assert_eq!("HTTPSConnection".to_snake_case(),"https_connection");
+        assert_eq!("version2Name".to_snake_case(),"version2_name");
+        assert_eq!("x".to_camel_case(),"x");
+        assert_eq!("".to_snake_case(),"");
+        assert_eq!("".to_camel_case(),"");
+        let snake = "max_line_width";
+        assert_eq!(snake.to_camel_case().to_snake_case(),snake);
     }
 
-    fn is_file(self) -> bool {
-        match self {
-            Ok(meta) => meta.is_file(),
-            Err(_) => false
+    #[cfg(unix)]
+    #[test]
+    fn test_dir_on_error() {
+        use std::sync::{Arc,Mutex};
+        let tmp = scratch_dir("on-error");
+        let dir = tmp.path();
+        for f in &["a","b","c"] {
+            ::write_all(dir.join(f),"");
+        }
+        let failed = Arc::new(Mutex::new(Vec::new()));
+        let log = failed.clone();
+        let mut iter = ::paths(dir).on_error(move |p,e| log.lock().unwrap().push((p.to_path_buf(),e.kind())));
+        let (first,_) = iter.next().unwrap();
+        // the directory has already been read, so the other entries
+        // still turn up, but they can't be looked at any more
+        for f in &["a","b","c"] {
+            let _ = fs::remove_file(dir.join(f));
         }
+        assert_eq!(iter.count(),0);
+        let mut failed = failed.lock().unwrap().iter().map(|(p,k)| (p.file_name().unwrap().to_owned(),*k)).to_vec();
+        failed.sort();
+        let mut expected = ["a","b","c"].iter()
+            .filter(|&&f| dir.join(f) != first)
+            .map(|f| (f.into(),::std::io::ErrorKind::NotFound))
+            .to_vec();
+        expected.sort();
+        assert_eq!(failed,expected);
     }
-}
 
+    #[test]
+    fn child_print_wrapped() {
+        if ! is_child() { return; }
+        let items = ["short","the quick brown fox jumps","unbreakable-token-here"];
+        println!("<<<");
+        items.iter().print_wrapped(12);
+        println!(">>>");
+    }
 
-/// quit this program, printing a message and returning a non-zero exit code.
-pub fn quit(msg: &str) -> !{
-    let text = format!("{} error: {}",argn_or(0,""),msg);
-    if std::env::var("EASY_DONT_QUIT_PANIC").is_ok() {
-        panic!(text);
-    } else {
-        writeln!(&mut io::stderr(),"{}",text).unwrap();
-        std::process::exit(1);
+    #[test]
+    fn test_print_wrapped() {
+        let out = run_child("child_print_wrapped");
+        let text = String::from_utf8_lossy(&out.stdout);
+        let start = text.find("<<<\n").unwrap() + 4;
+        let end = text.find(">>>").unwrap();
+        assert_eq!(&text[start..end],"short\nthe quick\nbrown fox\njumps\nunbreakable-token-here\n");
     }
-}
 
-/// a form of `quit` which works with the standard `Error` type.
-pub fn quit_err(e: &std::error::Error) -> ! {
-    quit(e.description());
-}
+    #[test]
+    fn child_quit_with_code() {
+        if ! is_child() { return; }
+        ::quit_with_code(3,"no match");
+    }
 
+    #[test]
+    fn test_quit_with_code() {
+        let out = run_child("child_quit_with_code");
+        assert_eq!(out.status.code(),Some(3));
+        assert!(String::from_utf8_lossy(&out.stderr).ends_with(" error: no match\n"));
+    }
 
-/// quit works like try, except it quits instead of returning.
-/// The error message is output using `quit_err`.
-///
-/// ```
-/// #[macro_use]
-/// extern crate easy_shortcuts;
-/// use std::fs;
-///
-/// fn main() {
-///     let md = quit!(fs::metadata("."));
-/// }
-/// ```
-#[macro_export]
-macro_rules! quit {
-    ($e:expr) => (match $e { Ok(val) => val, Err(err) => $crate::quit_err(&err) });
-}
+    #[test]
+    fn test_files_sorted_by() {
+        let tmp = scratch_dir("sorted-by");
+        let dir = tmp.path();
+        ::write_all(dir.join("small"),"a");
+        ::write_all(dir.join("large"),"abcdefgh");
+        ::write_all(dir.join("medium"),"abcd");
+        let names: Vec<_> = ::files_sorted_by(dir,|a,b| b.1.len().cmp(&a.1.len()))
+            .into_iter().map(|(p,_)| p.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names,["large","medium","small"]);
+    }
 
-/// get the nth command-line argument or return the default.
-///
-/// ```
-/// extern crate easy_shortcuts as es;
-///
-/// let arg = es::argn_or(1,"default");
-/// assert_eq!(arg,"default");
-/// ```
-pub fn argn_or(idx: usize, def: &str) -> String {
-    std::env::args().nth(idx).unwrap_or(def.to_string())
-}
+    #[test]
+    fn test_find_all() {
+        assert_eq!("the cat sat on the mat".find_all("at"),&[5,9,20]);
+        assert_eq!("héllo héllo".find_all("llo"),&[3,10]);
+        assert_eq!("aaa".find_all("aa"),&[0]);
+        assert!("the cat".find_all("dog").is_empty());
+        assert!("the cat".find_all("").is_empty());
+        assert!("".find_all("x").is_empty());
+    }
 
-/// get the nth argument or quit with a message.
-pub fn argn_err(idx: usize, msg: &str) -> String {
-    match std::env::args().nth(idx) {
-        Some(s) => s,
-        None => quit(&format!("no argument {}: {}",idx,msg))
+    #[test]
+    fn test_print_to() {
+        let mut out = Vec::new();
+        ["one","two"].iter().print_to(&mut out,"\n");
+        (1..4).print_to(&mut out,",");
+        assert_eq!(String::from_utf8(out).unwrap(),"one\ntwo\n1,2,3,");
     }
-}
 
-use std::path::Path;
+    #[test]
+    fn child_quit_err_display() {
+        if ! is_child() { return; }
+        // ConfigError only has a Display message, not a description
+        let inner = ::std::io::Error::new(::std::io::ErrorKind::NotFound,"no such file");
+        let res: Result<(),ConfigError> = Err(ConfigError(inner));
+        quit!(res);
+    }
 
-/// open a file for reading, quitting if there's any error.
-pub fn open<P: AsRef<Path>>(file: P) -> File {
-    match  File::open(&file) {
-        Ok(f) => f,
-        Err(e) => quit(&format!("open {:?} {}",file.as_ref(),e))
+    #[test]
+    fn test_quit_err_display() {
+        let out = run_child("child_quit_err_display");
+        assert_eq!(out.status.code(),Some(1));
+        let err = String::from_utf8_lossy(&out.stderr);
+        assert!(err.ends_with("error: cannot load config\n"),"{}",err);
     }
-}
 
-/// open a buffered file for reading, quitting if there's any error.
-pub fn open_buffered<P: AsRef<Path>>(file: P) -> io::BufReader<File> {
-    io::BufReader::new(open(file))
-}
+    #[test]
+    fn test_easy_error() {
+        fn parse(s: &str) -> Result<i32,::EasyError> {
+            s.parse().map_err(|_| format!("bad number {:?}",s).into())
+        }
+        fn missing() -> Result<String,::EasyError> {
+            Ok(fs::read_to_string("no-such-file")?)
+        }
+        assert_eq!(parse("42"),Ok(42));
+        assert_eq!(parse("x").unwrap_err().to_string(),"bad number \"x\"");
+        assert!(missing().is_err());
+        assert_eq!(::EasyError::from("oops"),::EasyError::new("oops"));
+    }
 
-/// create a file for writing, quitting if not possible.
-pub fn create<P: AsRef<Path>>(file: P) -> File {
-    match File::create(&file) {
-        Ok(f) => f,
-        Err(e) => quit(&format!("create {:?} {}",file.as_ref(),e))
+    #[test]
+    fn child_eprint() {
+        if ! is_child() { return; }
+        [1,2].iter().eprint("\n");
     }
-}
 
-/// read the contents of a file as a string, quitting otherwise
-pub fn read_to_string<P: AsRef<Path>>(file: P) -> String {
-    let mut f = open(file);
-    let mut s = String::new();
-    quit!(f.read_to_string(&mut s));
-    s
-}
+    #[test]
+    fn test_eprint() {
+        let out = run_child("child_eprint");
+        assert_eq!(String::from_utf8_lossy(&out.stderr),"1\n2\n");
+        assert!(! String::from_utf8_lossy(&out.stdout).contains("1\n2\n"));
+    }
 
-/// write a String to a new file, or quit
-pub fn write_all<P: AsRef<Path>>(file: P, buff: &str) {
-    quit!(create(file).write_all(&buff.as_bytes()));
-}
+    #[test]
+    fn child_or_die_with() {
+        if ! is_child() { return; }
+        let res: Result<i32,_> = "x".parse::<i32>();
+        res.or_die_with(|| format!("reading {}","count"));
+    }
 
-/// execute a shell command, combining stdout and stderr,
-/// and return the result as a string
-///
-/// ```
-/// extern crate easy_shortcuts as es;
-///
-/// let res = es::shell("rustc -V");
-/// assert!(res.starts_with("rustc"));
-/// ```
-pub fn shell(cmd: &str) -> String {
-    let o = Command::new(if cfg!(windows) {"cmd.exe"} else {"/bin/sh"})
-     .arg(if cfg!(windows) {"/c"} else {"-c"})
-     .arg(&format!("{} 2>&1",cmd))
-     .output()
-     .expect("failed to execute shell");
-    String::from_utf8_lossy(&o.stdout).trim_right_matches('\n').to_string()
-}
+    #[test]
+    fn test_or_die_with() {
+        let out = run_child("child_or_die_with");
+        assert_eq!(out.status.code(),Some(1));
+        let err = String::from_utf8_lossy(&out.stderr);
+        assert!(err.ends_with("error: reading count invalid digit found in string\n"),"{}",err);
+    }
 
-/// implements line iterator over a readable.
-pub struct LineIter<R: io::Read> {
-    iter: io::Lines<io::BufReader<R>>
-}
+    #[test]
+    fn test_dir_filters() {
+        let tmp = scratch_dir("dir-filters");
+        let dir = tmp.path();
+        ::write_all(dir.join("a.txt"),"hello");
+        ::write_all(dir.join("b.txt"),"");
+        ::write_all(dir.join("c.md"),"hello");
+        fs::create_dir(dir.join("d.txt")).unwrap();
+        let mut found = ::paths(dir)
+            .filter_meta(|m| m.is_file() && m.len() > 0)
+            .filter_path(|p| p.extension().is_some_and(|e| e == "txt"))
+            .map(|(p,_)| p)
+            .to_vec();
+        found.sort();
+        assert_eq!(found,&[dir.join("a.txt")]);
+    }
 
-/// iterate over all lines from a readable.
-/// The iterator is over String (will quit if
-/// there is an i/o error)
-pub fn lines<R: io::Read>(f: R) -> LineIter<R> {
-    LineIter{iter: io::BufReader::new(f).lines()}
-}
+    #[test]
+    fn test_count_by_sorted() {
+        let words = "the cat sat on the mat the end on";
+        assert_eq!(words.split(' ').count_by_sorted(|w| w.to_string()),
+            vec![("the".to_string(),3),("on".to_string(),2),("cat".to_string(),1),
+                 ("end".to_string(),1),("mat".to_string(),1),("sat".to_string(),1)]);
+        // by length
+        assert_eq!(words.split(' ').count_by_sorted(|w| w.len()),vec![(3,7),(2,2)]);
+        assert!(Vec::<i32>::new().into_iter().count_by_sorted(|n| *n).is_empty());
+    }
 
-impl <R: io::Read> Iterator for LineIter<R> {
-    type Item = String;
+    #[test]
+    fn test_list() {
+        let tmp = scratch_dir("list");
+        let dir = tmp.path();
+        ::write_all(dir.join("b.txt"),"hello");
+        ::write_all(dir.join("a.txt"),"");
+        fs::create_dir(dir.join("sub")).unwrap();
+        ::write_all(dir.join("sub/inner.txt"),"not listed");
+        assert_eq!(::list(dir),vec![
+            ("a.txt".to_string(),0,false),
+            ("b.txt".to_string(),5,false),
+            ("sub".to_string(),0,true)
+        ]);
+    }
 
-    fn next(&mut self) -> Option<String> {
-        let nxt = self.iter.next();
-        match nxt {
-            Some(p) => Some(quit!(p)),
-            None => None
-        }
+    #[test]
+    fn child_warn() {
+        if ! is_child() { return; }
+        ::warn("config not found, using defaults");
+        println!("still running");
     }
-}
 
-/// implements directory iterator over (path,metadata)
-/// created by `paths`
-pub struct DirIter {
-    iter: std::fs::ReadDir
-}
+    #[test]
+    fn test_warn() {
+        let out = run_child("child_warn");
+        assert!(out.status.success());
+        let err = String::from_utf8_lossy(&out.stderr);
+        assert!(err.ends_with(" warning: config not found, using defaults\n"),"{}",err);
+        assert!(String::from_utf8_lossy(&out.stdout).contains("still running"));
+    }
 
+    #[test]
+    fn child_print_no_delim() {
+        if ! is_child() { return; }
+        println!("<<<");
+        let n = (1..4).print_count("");
+        ["a","b"].iter().debug("");
+        println!("{}",n);
+        ::std::process::exit(0);
+    }
 
-impl Iterator for DirIter {
-    type Item = (path::PathBuf, fs::Metadata);
+    #[test]
+    fn test_print_no_delim() {
+        let out = run_child("child_print_no_delim");
+        assert!(String::from_utf8_lossy(&out.stdout).contains("<<<\n123\"a\"\"b\"3\n"));
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let nxt = self.iter.next();
-        match nxt {
-            None => None, // end of iterator
-            Some(me) => {
-                let entry = quit!(me);
-                let meta = quit!(entry.metadata());
-                Some((entry.path(),meta))
-            }
+    #[test]
+    fn test_interpolate() {
+        let lookup = |name: &str| if name == "x" {Some("42".to_string())} else {None};
+        assert_eq!("x={x}, y={y}".interpolate(lookup),"x=42, y={y}");
+        assert_eq!("{x}{x}".interpolate(lookup),"4242");
+        assert_eq!("{{x} {{{x}".interpolate(lookup),"{x} {42");
+        assert_eq!("open {x".interpolate(lookup),"open {x");
+        assert_eq!("{}".interpolate(lookup),"{}");
+        assert_eq!("{{x}} }} {x}}".interpolate(lookup),"{x} } 42}");
+        assert_eq!("a}b".interpolate(lookup),"a}b");
+        assert_eq!("no braces".interpolate(lookup),"no braces");
+    }
 
+    #[test]
+    fn test_extension_counts() {
+        let tmp = scratch_dir("extension-counts");
+        let dir = tmp.path();
+        for file in &["a.rs","b.rs","sub/c.rs","sub/d.txt","e.txt","Makefile","sub/README",".hidden.rs"] {
+            let path = dir.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            ::write_all(path,"");
         }
+        fs::create_dir(dir.join("data.rs")).unwrap();
+        assert_eq!(::extension_counts(dir),vec![
+            ("rs".to_string(),3),("".to_string(),2),("txt".to_string(),2)
+        ]);
     }
-}
 
-/// implements directory iterator over filenames
-pub struct FileNameIter {
-    iter: std::fs::ReadDir
-}
+    #[test]
+    fn child_quit_handler() {
+        use std::sync::{Arc,Mutex};
+        if ! is_child() { return; }
+        env::set_var("EASY_DONT_QUIT_PANIC","1");
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = seen.clone();
+        ::set_quit_handler(Box::new(move |msg| log.lock().unwrap().push(msg.to_string())));
+        let res = ::std::panic::catch_unwind(|| ::quit("first"));
+        assert!(res.is_err());
+        let res = ::std::panic::catch_unwind(|| ::quit_with_code(3,"second"));
+        assert!(res.is_err());
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(),2);
+        assert!(seen[0].ends_with(" error: first"));
+        assert!(seen[1].ends_with(" error: second"));
+        println!("handler ran");
+    }
 
-impl Iterator for FileNameIter {
-    type Item = String;
+    #[test]
+    fn test_quit_handler() {
+        let out = run_child("child_quit_handler");
+        assert!(out.status.success(),"{}",String::from_utf8_lossy(&out.stderr));
+        assert!(String::from_utf8_lossy(&out.stdout).contains("handler ran"));
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        match self.iter.next() {
-            None => None,
-            Some(me) => {
-                let entry = quit!(me);
-                let file = entry.file_name();
-                Some(file.to_string_lossy().to_string())
-            }
-        }
+    #[test]
+    fn test_join_long_first_value() {
+        // reserving as if every value were as long as the first
+        // would ask for hundreds of gigabytes here
+        let mut v = vec!["x".repeat(1 << 20)];
+        v.extend((0..200_000).map(|_| "a".to_string()));
+        let s = v.iter().join(',');
+        assert_eq!(s.len(),(1 << 20) + 2 * 200_000);
+        assert!(s.capacity() < 4 * s.len());
+        let mut out = String::new();
+        v.iter().join_to(',',&mut out);
+        assert_eq!(out,s);
+        assert_eq!(v.iter().concat_strings().len(),(1 << 20) + 200_000);
     }
 
-}
+    #[test]
+    fn child_print_large() {
+        if ! is_child() { return; }
+        (0..10_000).print("\n");
+        println!("done");
+    }
 
+    #[test]
+    fn test_print_large() {
+        let out = run_child("child_print_large");
+        let expected = (0..10_000).map(|n| format!("{}\n",n)).collect::<String>() + "done\n";
+        assert!(String::from_utf8_lossy(&out.stdout).contains(&expected));
+    }
 
-/// iterator over all entries in a directory.
-/// Returns a tuple of (`path::PathBuf`,`fs::Metadata`);
-/// will quit if the directory does not exist or there
-/// is an i/o error)
-pub fn paths<P: AsRef<Path>> (dir: P) -> DirIter {
-    match std::fs::read_dir(dir.as_ref()) {
-        Ok(s) => DirIter{iter: s},
-        Err(e) => quit(&format!("{:?} {}",dir.as_ref(),e))
+    #[test]
+    fn child_print_quits_midway() {
+        if ! is_child() { return; }
+        ["1","2","x","4"].iter().map(|s| s.parse::<i32>().or_die("bad")).print("\n");
     }
-}
 
-/// iterator over all files in a directory.
-/// Returns the files as strings;
-/// will quit if the directory does not exist or there
-/// is an i/o error)
-pub fn files<P: AsRef<Path>> (dir: P) -> FileNameIter {
-    match std::fs::read_dir(dir.as_ref()) {
-        Ok(s) => FileNameIter{iter: s},
-        Err(e) => quit(&format!("{:?} {}",dir.as_ref(),e))
+    #[test]
+    fn child_debug_quits_midway() {
+        if ! is_child() { return; }
+        // no newlines, so all this is still waiting to be written
+        print!("<<<");
+        ["1","2","x","4"].iter().map(|s| s.parse::<i32>().or_die("bad")).debug(",");
     }
-}
 
+    #[test]
+    fn test_print_quits_midway() {
+        let out = run_child("child_print_quits_midway");
+        assert_eq!(out.status.code(),Some(1));
+        assert!(String::from_utf8_lossy(&out.stdout).contains("1\n2\n"));
+        let out = run_child("child_debug_quits_midway");
+        assert_eq!(out.status.code(),Some(1));
+        assert!(String::from_utf8_lossy(&out.stdout).contains("<<<1,2,"));
+    }
 
-#[cfg(test)]
-mod tests {
-    use traits::*;
+    #[test]
+    fn child_quit_handler_quits() {
+        if ! is_child() { return; }
+        ::set_quit_handler(Box::new(|_| ::quit("from handler")));
+        ::quit("outer");
+    }
 
+    #[test]
+    fn child_usage_handler() {
+        if ! is_child() { return; }
+        ::set_quit_handler(Box::new(|msg| println!("handled: {}",msg)));
+        ::usage("usage: prog <file>");
+    }
 
     #[test]
-    fn test_to_vec() {
-        let v = "one two three".split_whitespace().to_vec();
-        assert_eq!(v,&["one","two","three"]);
+    fn test_quit_handler_reentry() {
+        // would deadlock if the handler were called holding the lock
+        let out = run_child("child_quit_handler_quits");
+        assert_eq!(out.status.code(),Some(1));
+        assert!(String::from_utf8_lossy(&out.stderr).ends_with(" error: from handler\n"));
+
+        let out = run_child("child_usage_handler");
+        assert_eq!(out.status.code(),Some(2));
+        assert!(String::from_utf8_lossy(&out.stdout).contains("handled: usage: prog <file>\n"));
     }
 
     #[test]
-    fn test_iter_join() {
-        // join an iterator over &str
-        let v = ["one","two","three"];
-        let s = v.into_iter().join(',');
-        assert_eq!(s,"one,two,three");
+    fn test_iterators_are_send() {
+        fn assert_send<T: Send>(_: &T) {}
+        assert_send(&::paths(".").on_error(|_,_| ()).filter_path(|_| true));
+        assert_send(&::all_paths(".").filter_meta(|_| true));
+    }
 
-        // join an iterator over String
-        let v = vec!["one".to_string(),"two".to_string(),"three".to_string()];
-        let s = v.into_iter().map(|s| s.to_uppercase()).join(',');
-        assert_eq!(s,"ONE,TWO,THREE");
+    #[test]
+    fn test_find_up() {
+        let here = fs::canonicalize(".").unwrap();
+        assert_eq!(::find_up("src","Cargo.toml"),Some(here.join("Cargo.toml")));
+        // from a relative path, the search goes above the current directory
+        let name = here.file_name().unwrap().to_str().unwrap();
+        assert_eq!(::find_up(".",name),Some(here.clone()));
+        assert_eq!(::find_up("no-such-dir","Cargo.toml"),None);
+    }
 
-        let s = ["one","two","three"].into_iter().prepend(" -L");
-        assert_eq!(s," -Lone -Ltwo -Lthree");
+    #[test]
+    fn child_unarchive_corrupt() {
+        if ! is_child() { return; }
+        let tmp = scratch_dir("corrupt-archive");
+        let dir = tmp.path();
+        // a name claiming to be 4GB long, with nothing after it
+        let mut bad = vec![b'f'];
+        bad.extend_from_slice(&u32::MAX.to_le_bytes());
+        bad.extend_from_slice(b"short");
+        fs::write(dir.join("bad"),&bad).unwrap();
+        ::unarchive(dir.join("bad"),dir.join("out"));
+    }
+
+    #[test]
+    fn child_unarchive_truncated() {
+        if ! is_child() { return; }
+        let tmp = scratch_dir("truncated-archive");
+        let dir = tmp.path();
+        let mut bad = vec![b'f'];
+        bad.extend_from_slice(&4u32.to_le_bytes());
+        bad.extend_from_slice(b"file");
+        bad.extend_from_slice(&u64::MAX.to_le_bytes());
+        bad.extend_from_slice(b"not much");
+        fs::write(dir.join("bad"),&bad).unwrap();
+        ::unarchive(dir.join("bad"),dir.join("out"));
+    }
+
+    #[test]
+    fn test_unarchive_corrupt() {
+        for child in &["child_unarchive_corrupt","child_unarchive_truncated"] {
+            let out = run_child(child);
+            assert_eq!(out.status.code(),Some(1));
+            let err = String::from_utf8_lossy(&out.stderr);
+            assert!(err.contains("corrupt archive: truncated"),"{}",err);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn child_archive_non_utf8() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+        if ! is_child() { return; }
+        let tmp = scratch_dir("non-utf8-archive");
+        let dir = tmp.path();
+        fs::create_dir(dir.join("src")).unwrap();
+        ::write_all(dir.join("src").join(OsStr::from_bytes(b"bad\xff")),"");
+        ::archive(dir.join("src"),dir.join("packed"));
     }
-}
 
+    #[cfg(unix)]
+    #[test]
+    fn test_archive_non_utf8() {
+        let out = run_child("child_archive_non_utf8");
+        assert_eq!(out.status.code(),Some(1));
+        let err = String::from_utf8_lossy(&out.stderr);
+        assert!(err.contains("non-UTF-8 file name"),"{}",err);
+    }
+}
@@ -0,0 +1,202 @@
+//! Walking large directory trees using several threads.
+//!
+//! Directories waiting to be read go into a shared queue; each worker
+//! thread takes the next directory from the queue, sends its entries
+//! back through a channel, and puts any subdirectories on the queue for
+//! whichever worker is free next. The walk is finished when the queue is
+//! empty and no worker is still reading a directory.
+//!
+//! Only available with the `threads` feature.
+
+use std::collections::{HashSet,VecDeque};
+use std::fs;
+use std::path::{Path,PathBuf};
+use std::sync::{Arc,Condvar,Mutex};
+use std::sync::mpsc::{channel,Receiver,Sender};
+use std::thread;
+use walk::{AllPaths,Walk};
+
+// an entry, or the message to quit with
+type Found = Result<(PathBuf,fs::Metadata),String>;
+
+struct Work {
+    // directories to read, with the depth of their entries
+    queue: VecDeque<(PathBuf,usize)>,
+    // directories queued or being read
+    pending: usize,
+    // set when the receiver has gone away
+    stopped: bool,
+}
+
+struct Shared {
+    work: Mutex<Work>,
+    ready: Condvar,
+    walk: Walk,
+    // the canonical directories entered, when following links
+    visited: Option<Mutex<HashSet<PathBuf>>>,
+}
+
+/// implements the parallel directory iterator over (path,metadata)
+/// created by `AllPaths::parallel`
+pub struct ParallelPaths {
+    rx: Receiver<Found>
+}
+
+impl AllPaths {
+    /// read the tree with `threads` worker threads, giving the same
+    /// entries as the sequential walk with all the same settings.
+    ///
+    /// The entries do **not** come in any particular order, and the order
+    /// will differ between runs. Will quit if there is an i/o error.
+    /// Only available with the `threads` feature.
+    ///
+    /// ```
+    /// extern crate easy_shortcuts as es;
+    ///
+    /// let found = es::all_paths(".").exclude("target").files_only().parallel(4)
+    ///     .any(|(p,_)| p.ends_with("src/lib.rs"));
+    /// assert!(found);
+    /// ```
+    pub fn parallel(self, threads: usize) -> ParallelPaths {
+        let (tx,rx) = channel();
+        let mut queue = VecDeque::new();
+        queue.push_back((self.root,0));
+        let shared = Arc::new(Shared {
+            work: Mutex::new(Work{queue, pending: 1, stopped: false}),
+            ready: Condvar::new(),
+            walk: self.walk,
+            visited: self.visited.map(Mutex::new),
+        });
+        for _ in 0..threads.max(1) {
+            let shared = shared.clone();
+            let tx = tx.clone();
+            thread::spawn(move || worker(&shared,&tx));
+        }
+        ParallelPaths{rx}
+    }
+}
+
+fn worker(shared: &Shared, tx: &Sender<Found>) {
+    while let Some((dir,depth)) = next_dir(shared) {
+        let mut subdirs = Vec::new();
+        let ok = read_dir(shared,&dir,depth,tx,&mut subdirs);
+        let mut work = shared.work.lock().unwrap();
+        if ! ok {
+            work.stopped = true;
+        }
+        work.pending += subdirs.len();
+        work.pending -= 1;
+        work.queue.extend(subdirs);
+        shared.ready.notify_all();
+    }
+}
+
+// wait for a directory to read; None when the walk is over
+fn next_dir(shared: &Shared) -> Option<(PathBuf,usize)> {
+    let mut work = shared.work.lock().unwrap();
+    loop {
+        if work.stopped || work.pending == 0 {
+            return None;
+        }
+        if let Some(dir) = work.queue.pop_front() {
+            return Some(dir);
+        }
+        work = shared.ready.wait(work).unwrap();
+    }
+}
+
+// send the wanted entries of a directory, collecting the subdirectories
+// to go into. False if the walk should stop.
+fn read_dir(shared: &Shared, dir: &Path, depth: usize, tx: &Sender<Found>, subdirs: &mut Vec<(PathBuf,usize)>) -> bool {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(ref e) if shared.walk.vanished(e) => return true,
+        Err(e) => {
+            let _ = tx.send(Err(format!("{:?} {}",dir,e)));
+            return false;
+        }
+    };
+    for entry in entries {
+        let visit = entry.and_then(|e| match shared.visited {
+            Some(ref visited) => shared.walk.visit(&e,depth,Some(&mut |p| visited.lock().unwrap().insert(p))),
+            None => shared.walk.visit(&e,depth,None)
+        });
+        let visit = match visit {
+            Ok(Some(visit)) => visit,
+            Ok(None) => continue,
+            Err(ref e) if shared.walk.vanished(e) => continue,
+            Err(e) => {
+                let _ = tx.send(Err(format!("{:?} {}",dir,e)));
+                return false;
+            }
+        };
+        if visit.descend {
+            subdirs.push((visit.path.clone(),depth + 1));
+        }
+        if visit.wanted && tx.send(Ok((visit.path,visit.meta))).is_err() {
+            return false;
+        }
+    }
+    true
+}
+
+impl Iterator for ParallelPaths {
+    type Item = (PathBuf,fs::Metadata);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.rx.recv() {
+            Ok(Ok(found)) => Some(found),
+            Ok(Err(msg)) => ::quit(&msg),
+            Err(_) => None // all the workers have finished
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::path::PathBuf;
+    use testing::scratch_tree;
+    use walk::{all_paths,AllPaths};
+
+    fn same_entries(make: &dyn Fn() -> AllPaths) {
+        let expected: HashSet<PathBuf> = make().map(|(p,_)| p).collect();
+        for &threads in &[1,3,8] {
+            let found: HashSet<_> = make().parallel(threads).map(|(p,_)| p).collect();
+            assert_eq!(found,expected);
+        }
+    }
+
+    #[test]
+    fn test_parallel_matches_sequential() {
+        let mut files = Vec::new();
+        for i in 0..5 {
+            for j in 0..4 {
+                for k in 0..3 {
+                    files.push(format!("d{}/e{}/f{}.txt",i,j,k));
+                }
+                files.push(format!("d{}/e{}/empty/.hidden",i,j));
+                files.push(format!("d{}/e{}/target/out.rs",i,j));
+            }
+            files.push(format!("top{}.rs",i));
+        }
+        let files: Vec<&str> = files.iter().map(|s| s.as_str()).collect();
+        let tmp = scratch_tree("parallel",&files);
+        let root = tmp.path().to_path_buf();
+        // a link back up, which is only followed once
+        #[cfg(unix)]
+        ::std::os::unix::fs::symlink(root.join("d0"),root.join("d0/e0/up")).unwrap();
+
+        same_entries(&|| all_paths(&root));
+        same_entries(&|| all_paths(&root).show_all());
+        same_entries(&|| all_paths(&root).exclude("target").files_only());
+        same_entries(&|| all_paths(&root).max_depth(1));
+        same_entries(&|| all_paths(&root).extension("rs").filter_path(|p| ! p.ends_with("top0.rs")));
+        same_entries(&|| all_paths(&root).dirs_only().follow_symlinks(true));
+        let found = all_paths(&root).exclude("d1").exclude("target").files_only().parallel(4).count();
+        assert_eq!(found,4 * 4 * 3 + 5);
+
+        // stopping early is fine
+        assert!(all_paths(&root).parallel(2).take(3).count() == 3);
+    }
+}
@@ -0,0 +1,123 @@
+//! A simple progress bar for operations where the total amount
+//! of work is known in advance.
+//!
+//! The bar is redrawn in place on standard error using `\r`, and only
+//! if standard error is a terminal, so it does not clutter logs or pipes.
+//!
+//! ```
+//! use easy_shortcuts::Bar;
+//!
+//! let files = ["a.txt","b.txt","c.txt"];
+//! let mut bar = Bar::new(files.len());
+//! for _file in &files {
+//!     // ... copy the file ...
+//!     bar.inc();
+//! }
+//! bar.finish();
+//! ```
+
+use std::io;
+use std::io::prelude::*;
+use std::io::IsTerminal;
+use std::time::{Duration,Instant};
+
+// don't redraw more often than this
+const REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+const BAR_WIDTH: usize = 30;
+
+/// a progress bar counting up to a known total.
+pub struct Bar {
+    done: usize,
+    total: usize,
+    visible: bool,
+    last_draw: Option<Instant>,
+}
+
+impl Bar {
+    /// a new bar for `total` steps of work.
+    pub fn new(total: usize) -> Bar {
+        Bar {
+            done: 0,
+            total,
+            visible: io::stderr().is_terminal(),
+            last_draw: None,
+        }
+    }
+
+    /// one more step has been done.
+    pub fn inc(&mut self) {
+        let n = self.done + 1;
+        self.set(n);
+    }
+
+    /// set the number of steps done so far, which is
+    /// never more than the total.
+    pub fn set(&mut self, n: usize) {
+        self.done = n.min(self.total);
+        let due = match self.last_draw {
+            Some(last) => last.elapsed() >= REDRAW_INTERVAL,
+            None => true
+        };
+        if due || self.done >= self.total {
+            self.draw();
+        }
+    }
+
+    /// draw the final state of the bar and end its line.
+    pub fn finish(&mut self) {
+        if self.visible {
+            self.draw();
+            let _ = writeln!(io::stderr());
+        }
+    }
+
+    fn draw(&mut self) {
+        if self.visible {
+            let mut err = io::stderr();
+            let _ = write!(err,"\r{}",render_bar(self.done,self.total,BAR_WIDTH));
+            let _ = err.flush();
+            self.last_draw = Some(Instant::now());
+        }
+    }
+}
+
+/// the text of a progress bar `width` characters wide, showing
+/// `done` out of `total` steps. An empty total counts as complete,
+/// and `done` is never shown as more than `total`.
+///
+/// ```
+/// use easy_shortcuts::render_bar;
+///
+/// assert_eq!(render_bar(5,10,8),"[####----] 50% (5/10)");
+/// ```
+pub fn render_bar(done: usize, total: usize, width: usize) -> String {
+    let done = done.min(total);
+    // an empty total is complete
+    let filled = (done * width).checked_div(total).unwrap_or(width);
+    let percent = (done * 100).checked_div(total).unwrap_or(100);
+    format!("[{}{}] {}% ({}/{})","#".repeat(filled),"-".repeat(width - filled),percent,done,total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_bar() {
+        assert_eq!(render_bar(0,10,10),"[----------] 0% (0/10)");
+        assert_eq!(render_bar(5,10,10),"[#####-----] 50% (5/10)");
+        assert_eq!(render_bar(10,10,10),"[##########] 100% (10/10)");
+        assert_eq!(render_bar(1,3,6),"[##----] 33% (1/3)");
+        assert_eq!(render_bar(0,0,4),"[####] 100% (0/0)");
+        assert_eq!(render_bar(12,10,10),"[##########] 100% (10/10)");
+    }
+
+    #[test]
+    fn test_bar_clamps() {
+        let mut bar = Bar::new(3);
+        bar.set(5);
+        assert_eq!(bar.done,3);
+        bar.inc();
+        assert_eq!(bar.done,3);
+    }
+}
@@ -0,0 +1,558 @@
+//! Walking a whole directory tree.
+//!
+//! `all_paths` returns an `AllPaths` iterator over every entry below
+//! a directory, which can be adjusted with builder methods before
+//! iterating.
+
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::fs;
+use std::io;
+use std::path::{Path,PathBuf};
+use std::time::{Duration,SystemTime};
+
+/// implements the recursive directory iterator over (path,metadata)
+/// created by `all_paths`
+pub struct AllPaths {
+    pub(crate) root: PathBuf,
+    // the directories being read, with the depth of their entries
+    stack: Vec<(fs::ReadDir,usize)>,
+    // the canonical directories entered, when following links
+    pub(crate) visited: Option<HashSet<PathBuf>>,
+    pub(crate) walk: Walk,
+}
+
+// what to give and what to go into, set by the `AllPaths` builder
+// methods. A parallel walk shares this between its threads.
+pub(crate) struct Walk {
+    max_depth: Option<usize>,
+    show_all: bool,
+    excluded: Vec<String>,
+    files_only: bool,
+    dirs_only: bool,
+    // the earliest modification time wanted; `Some(None)` if the
+    // window reaches back before the system clock can go
+    modified_since: Option<Option<SystemTime>>,
+    extension: Option<String>,
+    name: Option<String>,
+    filters: Vec<::PathFilter>,
+    skip_vanished: bool,
+}
+
+/// iterator over all entries below a directory, recursing
+/// into subdirectories. Returns a tuple of (`path::PathBuf`,`fs::Metadata`)
+/// like `paths`; each directory is followed by its own entries,
+/// depth-first. Symbolic links are not followed unless asked for with
+/// `follow_symlinks`, and hidden entries, whose names start with a dot,
+/// are skipped unless `show_all` is used.
+/// Will quit if the directory does not exist or there is an i/o error.
+///
+/// ```
+/// extern crate easy_shortcuts as es;
+///
+/// let found = es::all_paths(".").any(|(p,_)| p.ends_with("src/lib.rs"));
+/// assert!(found);
+/// ```
+pub fn all_paths<P: AsRef<Path>>(dir: P) -> AllPaths {
+    AllPaths {
+        root: dir.as_ref().to_path_buf(),
+        stack: vec![(read_dir(dir.as_ref()),0)],
+        visited: None,
+        walk: Walk {
+            max_depth: None,
+            show_all: false,
+            excluded: Vec::new(),
+            files_only: false,
+            dirs_only: false,
+            modified_since: None,
+            extension: None,
+            name: None,
+            filters: Vec::new(),
+            skip_vanished: false,
+        }
+    }
+}
+
+// a directory entry which is not skipped altogether
+pub(crate) struct Visit {
+    pub path: PathBuf,
+    pub meta: fs::Metadata,
+    // should it be given?
+    pub wanted: bool,
+    // should it be read as a directory?
+    pub descend: bool,
+}
+
+impl AllPaths {
+    /// skip any directory called `name`, with everything inside it.
+    /// Can be called several times to skip several names.
+    ///
+    /// ```
+    /// extern crate easy_shortcuts as es;
+    ///
+    /// let in_target = es::all_paths(".").exclude("target").exclude(".git")
+    ///     .any(|(p,_)| p.starts_with("./target"));
+    /// assert!(! in_target);
+    /// ```
+    pub fn exclude(mut self, name: &str) -> AllPaths {
+        self.walk.excluded.push(name.to_string());
+        self
+    }
+
+    /// only give regular files. Subdirectories are still searched.
+    /// Quits if `dirs_only` has also been asked for.
+    ///
+    /// ```
+    /// extern crate easy_shortcuts as es;
+    ///
+    /// assert!(es::all_paths("src").files_only().all(|(_,m)| m.is_file()));
+    /// ```
+    pub fn files_only(mut self) -> AllPaths {
+        if self.walk.dirs_only {
+            ::quit("all_paths: cannot ask for both files_only and dirs_only");
+        }
+        self.walk.files_only = true;
+        self
+    }
+
+    /// only give directories. Quits if `files_only` has also been asked for.
+    pub fn dirs_only(mut self) -> AllPaths {
+        if self.walk.files_only {
+            ::quit("all_paths: cannot ask for both files_only and dirs_only");
+        }
+        self.walk.dirs_only = true;
+        self
+    }
+
+    /// only give entries modified within the last `dur`. Entries
+    /// whose modification time cannot be read are left out.
+    ///
+    /// ```
+    /// extern crate easy_shortcuts as es;
+    /// use std::time::Duration;
+    ///
+    /// for (path,_) in es::all_paths(".").exclude("target").modified_since(Duration::from_secs(60)) {
+    ///     println!("recently changed: {}",path.display());
+    /// }
+    /// ```
+    pub fn modified_since(mut self, dur: Duration) -> AllPaths {
+        self.walk.modified_since = Some(SystemTime::now().checked_sub(dur));
+        self
+    }
+
+    /// include hidden files and directories, like `.git`.
+    ///
+    /// ```
+    /// extern crate easy_shortcuts as es;
+    ///
+    /// let hidden = es::all_paths(".").max_depth(0).show_all()
+    ///     .any(|(p,_)| p.ends_with(".gitignore"));
+    /// assert!(hidden);
+    /// ```
+    pub fn show_all(mut self) -> AllPaths {
+        self.walk.show_all = true;
+        self
+    }
+
+    /// skip entries which are deleted while the walk is going on,
+    /// rather than quitting. Useful when the tree is changing,
+    /// like a build directory being watched.
+    ///
+    /// ```
+    /// extern crate easy_shortcuts as es;
+    ///
+    /// let found = es::all_paths("src").skip_vanished().any(|(p,_)| p.ends_with("lib.rs"));
+    /// assert!(found);
+    /// ```
+    pub fn skip_vanished(mut self) -> AllPaths {
+        self.walk.skip_vanished = true;
+        self
+    }
+
+    /// follow symbolic links to directories, or not. By default links are
+    /// given as they are and not followed. When following, the metadata
+    /// given is for what the link points to, and each directory is only
+    /// entered once so that links cannot make the walk go round in circles.
+    /// This is found by comparing paths from `fs::canonicalize`.
+    pub fn follow_symlinks(mut self, yes: bool) -> AllPaths {
+        self.visited = if yes {
+            Some(fs::canonicalize(&self.root).into_iter().collect())
+        } else {
+            None
+        };
+        self
+    }
+
+    /// don't go more than `depth` directories down. With a depth of 0
+    /// only the entries of the directory itself are given; with 1 also
+    /// the entries of its subdirectories, and so on.
+    ///
+    /// ```
+    /// extern crate easy_shortcuts as es;
+    ///
+    /// let top = es::all_paths(".").max_depth(0).show_all().count();
+    /// assert_eq!(top,es::paths(".").count());
+    /// ```
+    pub fn max_depth(mut self, depth: usize) -> AllPaths {
+        self.walk.max_depth = Some(depth);
+        self
+    }
+
+    /// only give files with this extension, like `extension("rs")`.
+    /// The match is case-sensitive. Note that a file like `.gitignore`
+    /// has no extension.
+    ///
+    /// ```
+    /// extern crate easy_shortcuts as es;
+    ///
+    /// let sources = es::all_paths("src").extension("rs").count();
+    /// assert!(sources > 0);
+    /// ```
+    pub fn extension(mut self, ext: &str) -> AllPaths {
+        self.walk.extension = Some(ext.to_string());
+        self
+    }
+
+    /// only give entries with exactly this file name, like `name(".git")`.
+    pub fn name(mut self, name: &str) -> AllPaths {
+        self.walk.name = Some(name.to_string());
+        self
+    }
+
+    /// only give entries whose metadata passes `f`. Like `files_only`,
+    /// this does not stop the walk going into directories.
+    ///
+    /// ```
+    /// extern crate easy_shortcuts as es;
+    ///
+    /// let empty = es::all_paths("src").filter_meta(|m| m.is_file() && m.len() == 0).count();
+    /// assert_eq!(empty,0);
+    /// ```
+    pub fn filter_meta<F: Fn(&fs::Metadata) -> bool + Send + Sync + 'static>(mut self, f: F) -> AllPaths {
+        self.walk.filters.push(Box::new(move |_,m| f(m)));
+        self
+    }
+
+    /// only give entries whose path passes `f`. Use `exclude` to
+    /// skip whole directories.
+    ///
+    /// ```
+    /// extern crate easy_shortcuts as es;
+    ///
+    /// let tests = es::all_paths("src").filter_path(|p| p.to_string_lossy().contains("walk")).count();
+    /// assert_eq!(tests,1);
+    /// ```
+    pub fn filter_path<F: Fn(&Path) -> bool + Send + Sync + 'static>(mut self, f: F) -> AllPaths {
+        self.walk.filters.push(Box::new(move |p,_| f(p)));
+        self
+    }
+
+    /// the first entry for which `pred` is true. The walk stops there,
+    /// so the rest of the tree is not read.
+    ///
+    /// ```
+    /// extern crate easy_shortcuts as es;
+    ///
+    /// let (lib,_) = es::all_paths(".").exclude("target")
+    ///     .find_first(|p,m| m.is_file() && p.ends_with("lib.rs"))
+    ///     .unwrap();
+    /// assert!(lib.ends_with("src/lib.rs"));
+    /// ```
+    pub fn find_first<F>(mut self, pred: F) -> Option<(PathBuf,fs::Metadata)>
+    where F: Fn(&Path,&fs::Metadata) -> bool {
+        self.find(|(p,m)| pred(p,m))
+    }
+}
+
+impl Walk {
+    // what to do with an entry found `depth` directories down; `None`
+    // if it is skipped altogether. When following links, `enter` is
+    // given the canonical path of each directory, and says whether
+    // it has not been entered before.
+    pub(crate) fn visit(&self, entry: &fs::DirEntry, depth: usize, enter: Option<&mut dyn FnMut(PathBuf) -> bool>) -> io::Result<Option<Visit>> {
+        if ! self.show_all && entry.file_name().to_string_lossy().starts_with('.') {
+            return Ok(None);
+        }
+        let path = entry.path();
+        let mut meta = entry.metadata()?;
+        let mut descend = true;
+        if let Some(enter) = enter {
+            if meta.file_type().is_symlink() {
+                // a broken link is given as the link itself
+                if let Ok(target) = fs::metadata(&path) {
+                    meta = target;
+                }
+            }
+            if meta.is_dir() {
+                descend = fs::canonicalize(&path).map(enter).unwrap_or(false);
+            }
+        }
+        if meta.is_dir() {
+            if self.is_excluded(&path) {
+                return Ok(None);
+            }
+            descend = descend && self.max_depth.map_or(true,|max| depth < max);
+        } else {
+            descend = false;
+        }
+        let wanted = self.wanted(&path,&meta);
+        Ok(Some(Visit{path,meta,wanted,descend}))
+    }
+
+    fn wanted(&self, path: &Path, meta: &fs::Metadata) -> bool {
+        if (self.files_only && ! meta.is_file()) || (self.dirs_only && ! meta.is_dir()) {
+            return false;
+        }
+        if let Some(ref ext) = self.extension {
+            if ! meta.is_file() || path.extension() != Some(OsStr::new(ext)) {
+                return false;
+            }
+        }
+        if let Some(ref name) = self.name {
+            if path.file_name() != Some(OsStr::new(name)) {
+                return false;
+            }
+        }
+        if ! self.filters.iter().all(|f| f(path,meta)) {
+            return false;
+        }
+        match (self.modified_since,meta.modified()) {
+            (None,_) => true,
+            (Some(_),Err(_)) => false,
+            (Some(None),Ok(_)) => true,
+            (Some(Some(since)),Ok(modified)) => modified > since
+        }
+    }
+
+    // is this error from an entry that has gone, and can be skipped?
+    pub(crate) fn vanished(&self, e: &io::Error) -> bool {
+        self.skip_vanished && e.kind() == io::ErrorKind::NotFound
+    }
+
+    fn is_excluded(&self, path: &Path) -> bool {
+        match path.file_name() {
+            Some(name) => self.excluded.iter().any(|e| name == e.as_str()),
+            None => false
+        }
+    }
+}
+
+fn read_dir(dir: &Path) -> fs::ReadDir {
+    match fs::read_dir(dir) {
+        Ok(s) => s,
+        Err(e) => ::quit(&format!("{:?} {}",dir,e))
+    }
+}
+
+impl Iterator for AllPaths {
+    type Item = (PathBuf,fs::Metadata);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (ref mut iter,depth) = *self.stack.last_mut()?;
+            let entry = match iter.next() {
+                Some(Err(ref e)) if self.walk.vanished(e) => continue,
+                Some(entry) => quit!(entry),
+                None => {
+                    self.stack.pop();
+                    continue;
+                }
+            };
+            let visit = match self.visited {
+                Some(ref mut visited) => self.walk.visit(&entry,depth,Some(&mut |p| visited.insert(p))),
+                None => self.walk.visit(&entry,depth,None)
+            };
+            let visit = match visit {
+                Ok(Some(visit)) => visit,
+                Ok(None) => continue,
+                Err(ref e) if self.walk.vanished(e) => continue,
+                Err(e) => ::quit_err(&e)
+            };
+            if visit.descend {
+                match fs::read_dir(&visit.path) {
+                    Ok(iter) => self.stack.push((iter,depth + 1)),
+                    Err(ref e) if self.walk.vanished(e) => continue,
+                    Err(e) => ::quit(&format!("{:?} {}",visit.path,e))
+                }
+            }
+            if visit.wanted {
+                return Some((visit.path,visit.meta));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use traits::*;
+    use testing::scratch_tree;
+
+    // the paths found relative to the root, sorted
+    fn relative(root: &Path, iter: AllPaths) -> Vec<String> {
+        let mut res = iter
+            .map(|(p,_)| p.strip_prefix(root).unwrap().to_string_lossy().replace('\\',"/"))
+            .to_vec();
+        res.sort();
+        res
+    }
+
+    #[test]
+    fn test_all_paths() {
+        let tmp = scratch_tree("all",&["a.txt","sub/b.txt","sub/deeper/c.txt"]);
+        let root = tmp.path();
+        fs::create_dir(root.join("empty")).unwrap();
+        assert_eq!(relative(root,all_paths(root)),
+            &["a.txt","empty","sub","sub/b.txt","sub/deeper","sub/deeper/c.txt"]);
+
+        // a directory comes just before its contents
+        let order = all_paths(root).map(|(p,_)| p).to_vec();
+        let pos = |rel: &str| order.iter().position(|p| *p == root.join(rel)).unwrap();
+        assert_eq!(pos("sub/deeper") + 1,pos("sub/deeper/c.txt"));
+    }
+
+    #[test]
+    fn test_exclude() {
+        let tmp = scratch_tree("exclude",&["main.rs","target/top.txt","sub/target/junk.txt","sub/keep.txt","cache/x","target.txt"]);
+        let root = tmp.path();
+        assert_eq!(relative(root,all_paths(root).exclude("target")),
+            &["cache","cache/x","main.rs","sub","sub/keep.txt","target.txt"]);
+        assert_eq!(relative(root,all_paths(root).exclude("target").exclude("cache")),
+            &["main.rs","sub","sub/keep.txt","target.txt"]);
+    }
+
+    #[test]
+    fn test_files_or_dirs_only() {
+        let tmp = scratch_tree("only",&["a.txt","sub/b.txt","sub/deeper/c.txt"]);
+        let root = tmp.path();
+        assert_eq!(relative(root,all_paths(root).files_only()),
+            &["a.txt","sub/b.txt","sub/deeper/c.txt"]);
+        assert_eq!(relative(root,all_paths(root).dirs_only()),
+            &["sub","sub/deeper"]);
+        assert_eq!(relative(root,all_paths(root).exclude("deeper").files_only()),
+            &["a.txt","sub/b.txt"]);
+    }
+
+    #[test]
+    fn test_max_depth() {
+        let tmp = scratch_tree("depth",&["a/b/c/deep.txt","a/b/mid.txt","a/top.txt","root.txt"]);
+        let root = tmp.path();
+        assert_eq!(relative(root,all_paths(root).max_depth(0)),&["a","root.txt"]);
+        assert_eq!(relative(root,all_paths(root).max_depth(1)),
+            &["a","a/b","a/top.txt","root.txt"]);
+        assert_eq!(relative(root,all_paths(root).max_depth(2)),
+            &["a","a/b","a/b/c","a/b/mid.txt","a/top.txt","root.txt"]);
+        assert_eq!(all_paths(root).max_depth(3).count(),all_paths(root).count());
+    }
+
+    #[test]
+    fn test_show_all() {
+        let tmp = scratch_tree("hidden",&[".hidden",".git/config","src/.keep","src/main.rs"]);
+        let root = tmp.path();
+        assert_eq!(relative(root,all_paths(root)),&["src","src/main.rs"]);
+        assert_eq!(relative(root,all_paths(root).show_all()),
+            &[".git",".git/config",".hidden","src","src/.keep","src/main.rs"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_follow_symlinks() {
+        use std::os::unix::fs::symlink;
+        let tmp = scratch_tree("symlinks",&["a/file","b/file"]);
+        let root = tmp.path();
+        symlink(root,root.join("a/loop")).unwrap();
+        symlink(root.join("b"),root.join("a/sub")).unwrap();
+        symlink(root.join("nowhere"),root.join("broken")).unwrap();
+
+        let found = relative(root,all_paths(root));
+        assert_eq!(found,&["a","a/file","a/loop","a/sub","b","b/file","broken"]);
+
+        // each real directory is only entered once, whichever way it is reached
+        let found = relative(root,all_paths(root).follow_symlinks(true));
+        assert!(found.len() == 7,"{:?}",found);
+        assert!(found.contains(&"a/loop".to_string()));
+        assert!(! found.iter().any(|p| p.starts_with("a/loop/")));
+        let via_link = found.contains(&"a/sub/file".to_string());
+        assert!(via_link != found.contains(&"b/file".to_string()));
+
+        let dirs = relative(root,all_paths(root).follow_symlinks(true).dirs_only());
+        assert_eq!(dirs,&["a","a/loop","a/sub","b"]);
+    }
+
+    #[test]
+    fn test_find_first() {
+        let tmp = scratch_tree("find-first",&["a/b/c/d/MARKER","a/other.txt","z/later.txt"]);
+        let root = tmp.path();
+        let (path,meta) = all_paths(root).find_first(|p,_| p.ends_with("MARKER")).unwrap();
+        assert_eq!(path,root.join("a/b/c/d/MARKER"));
+        assert!(meta.is_file());
+        assert!(all_paths(root).find_first(|p,_| p.ends_with("nothing")).is_none());
+    }
+
+    #[test]
+    fn test_extension_and_name() {
+        let tmp = scratch_tree("extension",&["main.rs","notes.txt","README","sub/lib.rs","sub/README","sub/.gitignore","target/out.rs","LOUD.RS"]);
+        let root = tmp.path();
+        fs::create_dir(root.join("sub/data.rs")).unwrap();
+        assert_eq!(relative(root,all_paths(root).extension("rs")),
+            &["main.rs","sub/lib.rs","target/out.rs"]);
+        assert_eq!(relative(root,all_paths(root).exclude("target").files_only().extension("rs")),
+            &["main.rs","sub/lib.rs"]);
+        assert!(all_paths(root).extension("gitignore").next().is_none());
+        assert!(all_paths(root).extension("").next().is_none());
+        assert_eq!(relative(root,all_paths(root).name("README")),&["README","sub/README"]);
+        assert_eq!(relative(root,all_paths(root).show_all().name(".gitignore")),&["sub/.gitignore"]);
+        assert_eq!(relative(root,all_paths(root).name("data.rs").dirs_only()),&["sub/data.rs"]);
+    }
+
+    #[test]
+    fn test_filters() {
+        let tmp = scratch_tree("filters",&["a.txt","b.md","sub/c.txt","sub/deeper/d.txt"]);
+        let root = tmp.path();
+        ::write_all(root.join("sub/c.txt"),"not empty");
+        fs::create_dir(root.join("sub/dir.txt")).unwrap();
+        assert_eq!(relative(root,all_paths(root).filter_meta(|m| m.is_file())
+                .filter_path(|p| p.extension().is_some_and(|e| e == "txt"))),
+            &["a.txt","sub/c.txt","sub/deeper/d.txt"]);
+        assert_eq!(relative(root,all_paths(root).filter_path(|p| p.ends_with("deeper/d.txt"))),
+            &["sub/deeper/d.txt"]);
+        assert_eq!(relative(root,all_paths(root).filter_meta(|m| m.len() > 0).files_only()),
+            &["sub/c.txt"]);
+    }
+
+    #[test]
+    fn test_modified_since() {
+        use std::thread;
+        let tmp = scratch_tree("modified",&["new.txt","old.txt"]);
+        let root = tmp.path();
+        let modified = |name: &str| fs::metadata(root.join(name)).unwrap().modified().unwrap();
+        // write new.txt until it is well clear of old.txt
+        let gap = Duration::from_millis(100);
+        while modified("new.txt").duration_since(modified("old.txt")).map_or(true,|d| d < gap) {
+            thread::sleep(Duration::from_millis(20));
+            ::write_all(root.join("new.txt"),"");
+        }
+        let between = modified("old.txt") + gap / 2;
+        let since = |t: SystemTime| SystemTime::now().duration_since(t).unwrap();
+        assert_eq!(relative(root,all_paths(root).modified_since(since(between))),&["new.txt"]);
+        let before = modified("old.txt") - Duration::from_secs(3600);
+        assert_eq!(relative(root,all_paths(root).modified_since(since(before))),&["new.txt","old.txt"]);
+        // reaching back before the epoch is fine
+        assert_eq!(relative(root,all_paths(root).modified_since(Duration::MAX)),&["new.txt","old.txt"]);
+    }
+
+    #[test]
+    fn test_skip_vanished() {
+        let files: Vec<String> = (0..10).map(|i| format!("d{}/sub/f.txt",i)).collect();
+        let files: Vec<&str> = files.iter().map(|s| s.as_str()).collect();
+        let tmp = scratch_tree("vanished",&files);
+        let root = tmp.path();
+        let mut iter = all_paths(root).skip_vanished();
+        assert!(iter.next().is_some());
+        // the rest of the root directory has already been listed
+        for i in 0..10 {
+            fs::remove_dir_all(root.join(format!("d{}",i))).unwrap();
+        }
+        assert!(iter.count() < 3);
+    }
+}